@@ -0,0 +1,250 @@
+/*
+ * Rust implementation of vscode-uri
+ * https://github.com/microsoft/vscode-uri
+ */
+
+use crate::uri::{encode_uri_component_fast, ErrorContext, UriComponent, UriError};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/**
+ * Which characters are percent-encoded when a component is formatted.
+ * Non-ASCII characters and '%' are always encoded, since components are stored decoded.
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EncodingSet {
+    /**
+     * The encoding of vscode-uri's `toString()`: everything but the unreserved characters is
+     * encoded, except for '/' in the path and '[', ']' and ':' in the host.
+     */
+    #[default]
+    VsCode,
+    /**
+     * Only the characters that RFC 3986 does not allow in the component are encoded, so
+     * sub-delimiters (e.g. '+', '=', ';'), ':' and '@' are kept where the grammar allows them.
+     */
+    Rfc3986,
+    /**
+     * The percent-encode sets of the WHATWG URL standard: the userinfo, path, query (or
+     * special-query, for special schemes such as `http`) and fragment sets, and the C0 control
+     * set for hosts.
+     */
+    Whatwg,
+}
+
+/**
+ * The encoding set to use for each component in `URI::to_string_with`.
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EncodeOptions {
+    /** The user name and password. */
+    pub userinfo: EncodingSet,
+    pub host: EncodingSet,
+    pub path: EncodingSet,
+    pub query: EncodingSet,
+    pub fragment: EncodingSet,
+}
+
+impl EncodeOptions {
+    /**
+     * Uses `set` for all components.
+     */
+    pub fn all(set: EncodingSet) -> Self {
+        EncodeOptions {
+            userinfo: set,
+            host: set,
+            path: set,
+            query: set,
+            fragment: set,
+        }
+    }
+
+    pub(crate) fn set_for(&self, component: Component) -> EncodingSet {
+        match component {
+            Component::User | Component::Password => self.userinfo,
+            Component::Host => self.host,
+            Component::Path => self.path,
+            Component::Query => self.query,
+            Component::Fragment => self.fragment,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Component {
+    User,
+    Password,
+    Host,
+    Path,
+    Query,
+    Fragment,
+}
+
+impl EncodingSet {
+    pub(crate) fn encode(self, value: &str, component: Component) -> String {
+        self.encode_for_scheme(value, component, "")
+    }
+
+    /**
+     * Like `encode`, for a component of a URI with the scheme `scheme`, which decides between
+     * the query and the special-query set of `Whatwg`.
+     */
+    pub(crate) fn encode_for_scheme(
+        self,
+        value: &str,
+        component: Component,
+        scheme: &str,
+    ) -> String {
+        let allows = match self {
+            EncodingSet::VsCode => {
+                return encode_uri_component_fast(
+                    value,
+                    component == Component::Path,
+                    matches!(component, Component::Password | Component::Host),
+                )
+            }
+            EncodingSet::Rfc3986 => rfc3986_allows,
+            EncodingSet::Whatwg if is_special_scheme(scheme) => whatwg_special_allows,
+            EncodingSet::Whatwg => whatwg_allows,
+        };
+
+        let mut res = String::with_capacity(value.len());
+        for c in value.chars() {
+            if c.is_ascii() && c != '%' && allows(c as u8, component) {
+                res.push(c);
+            } else {
+                let mut buf = [0; 4];
+                for b in c.encode_utf8(&mut buf).bytes() {
+                    res.push_str(&format!("%{:02X}", b));
+                }
+            }
+        }
+        res
+    }
+}
+
+fn rfc3986_allows(c: u8, component: Component) -> bool {
+    let unreserved = c.is_ascii_alphanumeric() || matches!(c, b'-' | b'.' | b'_' | b'~');
+    let sub_delim = matches!(
+        c,
+        b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'='
+    );
+    unreserved
+        || sub_delim
+        || match component {
+            // the user name is followed by ':' if there is a password
+            Component::User => false,
+            Component::Password => c == b':',
+            // IP literals, e.g. `[::1]`
+            Component::Host => matches!(c, b'[' | b']' | b':'),
+            Component::Path => matches!(c, b':' | b'@' | b'/'),
+            Component::Query | Component::Fragment => matches!(c, b':' | b'@' | b'/' | b'?'),
+        }
+}
+
+fn whatwg_allows(c: u8, component: Component) -> bool {
+    if !(0x20..0x7f).contains(&c) {
+        return false;
+    }
+    let fragment_set = matches!(c, b' ' | b'"' | b'<' | b'>' | b'`');
+    let query_set = matches!(c, b' ' | b'"' | b'#' | b'<' | b'>');
+    let path_set = query_set || matches!(c, b'?' | b'`' | b'{' | b'}');
+    let userinfo_set = path_set
+        || matches!(
+            c,
+            b'/' | b':' | b';' | b'=' | b'@' | b'[' | b'\\' | b']' | b'^' | b'|'
+        );
+    match component {
+        Component::User | Component::Password => !userinfo_set,
+        // opaque hosts only use the C0 control set, but '@', '/', '?' and '#' would end the host
+        Component::Host => !matches!(c, b'@' | b'/' | b'?' | b'#'),
+        // '\' is a path separator for special schemes
+        Component::Path => !path_set && c != b'\\',
+        Component::Query => !query_set,
+        Component::Fragment => !fragment_set,
+    }
+}
+
+// The special-query set also has '\''
+fn whatwg_special_allows(c: u8, component: Component) -> bool {
+    whatwg_allows(c, component) && !(component == Component::Query && c == b'\'')
+}
+
+// The special schemes of the WHATWG URL standard
+fn is_special_scheme(scheme: &str) -> bool {
+    ["ftp", "file", "http", "https", "ws", "wss"]
+        .iter()
+        .any(|special| scheme.eq_ignore_ascii_case(special))
+}
+
+/**
+ * Strictly decodes the percent-encoded text of a URI component.
+ * Fails with `UriError::InvalidPercentEncoding` at the offending '%' if an escape is malformed
+ * (e.g. `%zz` or a truncated `%E`) or if the escapes do not decode to UTF-8 (e.g. `%E0%A4`).
+ */
+pub fn decode_percent(value: &str, component: UriComponent) -> Result<String, UriError> {
+    let (bytes, offsets) = decode(value, component)?;
+    String::from_utf8(bytes).map_err(|err| {
+        // the escapes of the invalid sequence, or of the incomplete one at the end
+        let err = err.utf8_error();
+        let start = offsets[err.valid_up_to()];
+        let end = err
+            .error_len()
+            .and_then(|len| offsets.get(err.valid_up_to() + len).copied())
+            .unwrap_or(value.len());
+        UriError::InvalidPercentEncoding {
+            context: Box::new(ErrorContext {
+                input: value.to_string(),
+                span: start..end,
+                component,
+            }),
+        }
+    })
+}
+
+/**
+ * Decodes the percent-encoded text of a URI component to bytes, e.g. the path of a file on a
+ * file system that does not use UTF-8. Only malformed escapes are rejected.
+ */
+pub fn decode_percent_bytes(value: &str, component: UriComponent) -> Result<Vec<u8>, UriError> {
+    decode(value, component).map(|(bytes, _)| bytes)
+}
+
+// Returns the decoded bytes and, for each of them, its offset in `value`.
+fn decode(value: &str, component: UriComponent) -> Result<(Vec<u8>, Vec<usize>), UriError> {
+    let input = value.as_bytes();
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut offsets = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] == b'%' {
+            let hex = |idx: usize| input.get(idx).and_then(|b| (*b as char).to_digit(16));
+            match (hex(i + 1), hex(i + 2)) {
+                (Some(high), Some(low)) => bytes.push((high << 4 | low) as u8),
+                _ => {
+                    // the '%' and the (at most two) characters that should be hex digits
+                    let mut end = (i + 3).min(input.len());
+                    while !value.is_char_boundary(end) {
+                        end += 1;
+                    }
+                    return Err(UriError::InvalidPercentEncoding {
+                        context: Box::new(ErrorContext {
+                            input: value.to_string(),
+                            span: i..end,
+                            component,
+                        }),
+                    });
+                }
+            }
+            offsets.push(i);
+            i += 3;
+        } else {
+            bytes.push(input[i]);
+            offsets.push(i);
+            i += 1;
+        }
+    }
+    Ok((bytes, offsets))
+}
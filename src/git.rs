@@ -0,0 +1,283 @@
+/*
+ * Rust implementation of vscode-uri
+ * https://github.com/microsoft/vscode-uri
+ */
+
+use crate::schemas::Schemas;
+use crate::uri::{URIChange, UriError, URI};
+
+/**
+ * The JSON query of a `git:` URI, as written by the built-in Git extension.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitUriParams {
+    /** The file system path of the resource. */
+    pub path: String,
+    /** The revision, e.g. `HEAD`, `~` (index) or a commit hash. */
+    pub git_ref: String,
+    /** The file system path of the parent repository if the resource is a submodule. */
+    pub submodule_of: Option<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct GitUriOptions {
+    pub submodule_of: Option<String>,
+    /** Appends `.git` to the path so that the resource is not picked up by file type associations. */
+    pub replace_file_extension: bool,
+    /** Uses another scheme than `git`. */
+    pub scheme: Option<String>,
+}
+
+/**
+ * Helpers for `git:` URIs, e.g. `git:/repo/a.ts?{"path":"/repo/a.ts","ref":"HEAD"}`.
+ */
+pub struct GitUri;
+
+impl GitUri {
+    pub const SCHEME: &'static str = Schemas::GIT;
+
+    /**
+     * Returns the URI of `uri` at revision `git_ref`, like the Git extension's `toGitUri`.
+     */
+    pub fn generate(uri: &URI, git_ref: &str) -> Result<URI, UriError> {
+        Self::generate_with(uri, git_ref, &GitUriOptions::default())
+    }
+
+    pub fn generate_with(
+        uri: &URI,
+        git_ref: &str,
+        options: &GitUriOptions,
+    ) -> Result<URI, UriError> {
+        let params = GitUriParams {
+            path: uri.fs_path().to_string_lossy().to_string(),
+            git_ref: git_ref.to_string(),
+            submodule_of: options.submodule_of.clone(),
+        };
+
+        let mut path = uri.path().to_string();
+        if options.replace_file_extension {
+            path.push_str(".git");
+        } else if options.submodule_of.is_some() {
+            path.push_str(".diff");
+        }
+
+        uri.with(URIChange {
+            scheme: Some(
+                options
+                    .scheme
+                    .clone()
+                    .unwrap_or_else(|| Self::SCHEME.to_string()),
+            ),
+            path: Some(path),
+            query: Some(params.to_json()),
+            ..Default::default()
+        })
+    }
+
+    /**
+     * Returns the parameters encoded in the query of a git URI, like the Git extension's `fromGitUri`.
+     * Returns `None` if the query is not a JSON object with string `path` and `ref` properties.
+     */
+    pub fn parse(uri: &URI) -> Option<GitUriParams> {
+        GitUriParams::from_json(uri.query())
+    }
+
+    /**
+     * Returns the `file:` URI of the resource a git URI refers to.
+     */
+    pub fn to_file(uri: &URI) -> Option<URI> {
+        if uri.scheme() != Self::SCHEME {
+            return None;
+        }
+        URI::file(Self::parse(uri)?.path).ok()
+    }
+}
+
+impl GitUriParams {
+    /**
+     * Serializes the parameters like `JSON.stringify`.
+     */
+    pub fn to_json(&self) -> String {
+        let mut res = String::from("{");
+        write_json_property(&mut res, "path", &self.path);
+        res.push(',');
+        write_json_property(&mut res, "ref", &self.git_ref);
+        if let Some(submodule_of) = &self.submodule_of {
+            res.push(',');
+            write_json_property(&mut res, "submoduleOf", submodule_of);
+        }
+        res.push('}');
+        res
+    }
+
+    pub fn from_json(json: &str) -> Option<Self> {
+        let mut path = None;
+        let mut git_ref = None;
+        let mut submodule_of = None;
+        for (key, value) in parse_json_object(json)? {
+            match key.as_str() {
+                "path" => path = Some(value?),
+                "ref" => git_ref = Some(value?),
+                "submoduleOf" => submodule_of = value,
+                _ => {}
+            }
+        }
+        Some(GitUriParams {
+            path: path?,
+            git_ref: git_ref?,
+            submodule_of,
+        })
+    }
+}
+
+fn write_json_property(res: &mut String, key: &str, value: &str) {
+    write_json_string(res, key);
+    res.push(':');
+    write_json_string(res, value);
+}
+
+fn write_json_string(res: &mut String, value: &str) {
+    res.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            '\u{08}' => res.push_str("\\b"),
+            '\u{0c}' => res.push_str("\\f"),
+            c if (c as u32) < 0x20 => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+}
+
+// Parses a flat JSON object. Values that are not strings are returned as `None`;
+// nested objects and arrays are not supported.
+fn parse_json_object(json: &str) -> Option<Vec<(String, Option<String>)>> {
+    let mut parser = JsonParser {
+        chars: json.chars().peekable(),
+    };
+    let mut properties = Vec::new();
+
+    parser.expect('{')?;
+    if parser.peek() == Some('}') {
+        parser.expect('}')?;
+    } else {
+        loop {
+            let key = parser.string()?;
+            parser.expect(':')?;
+            let value = if parser.peek() == Some('"') {
+                Some(parser.string()?)
+            } else {
+                parser.literal()?;
+                None
+            };
+            properties.push((key, value));
+            match parser.next()? {
+                ',' => continue,
+                '}' => break,
+                _ => return None,
+            }
+        }
+    }
+
+    if parser.peek().is_some() {
+        return None;
+    }
+    Some(properties)
+}
+
+struct JsonParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl JsonParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .chars
+            .peek()
+            .is_some_and(|c| matches!(c, ' ' | '\t' | '\n' | '\r'))
+        {
+            self.chars.next();
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.peek().copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.next()
+    }
+
+    fn expect(&mut self, expected: char) -> Option<()> {
+        (self.next()? == expected).then_some(())
+    }
+
+    // Numbers, `true`, `false` and `null`.
+    fn literal(&mut self) -> Option<()> {
+        self.skip_whitespace();
+        let mut len = 0;
+        while self
+            .chars
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.'))
+        {
+            self.chars.next();
+            len += 1;
+        }
+        (len > 0).then_some(())
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.expect('"')?;
+        let mut res = String::new();
+        loop {
+            match self.chars.next()? {
+                '"' => return Some(res),
+                '\\' => match self.chars.next()? {
+                    '"' => res.push('"'),
+                    '\\' => res.push('\\'),
+                    '/' => res.push('/'),
+                    'b' => res.push('\u{08}'),
+                    'f' => res.push('\u{0c}'),
+                    'n' => res.push('\n'),
+                    'r' => res.push('\r'),
+                    't' => res.push('\t'),
+                    'u' => {
+                        let high = self.hex4()?;
+                        let code = if (0xD800..0xDC00).contains(&high) {
+                            if self.chars.next()? != '\\' || self.chars.next()? != 'u' {
+                                return None;
+                            }
+                            let low = self.hex4()?;
+                            if !(0xDC00..0xE000).contains(&low) {
+                                return None;
+                            }
+                            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                        } else {
+                            high
+                        };
+                        res.push(char::from_u32(code)?);
+                    }
+                    _ => return None,
+                },
+                c if (c as u32) < 0x20 => return None,
+                c => res.push(c),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let mut value = 0;
+        for _ in 0..4 {
+            value = value * 16 + self.chars.next()?.to_digit(16)?;
+        }
+        Some(value)
+    }
+}
@@ -0,0 +1,301 @@
+/*
+ * Rust implementation of vscode-uri
+ * https://github.com/microsoft/vscode-uri
+ */
+
+use crate::uri::{ErrorContext, UriComponent, UriError, URI};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use regex::{Regex, RegexBuilder};
+
+const GLOBSTAR: &str = "**";
+const GLOB_SPLIT: char = '/';
+const PATH_REGEX: &str = r"[/\\]";
+const NO_PATH_REGEX: &str = r"[^/\\]";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GlobOptions {
+    /** Match paths case-insensitively. */
+    pub ignore_case: bool,
+}
+
+/**
+ * A glob pattern that is matched relative to a base URI, like VS Code's `RelativePattern`.
+ * Only resources at or below `base` can match.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelativePattern {
+    pub base: URI,
+    pub pattern: String,
+}
+
+impl RelativePattern {
+    pub fn new(base: URI, pattern: impl Into<String>) -> Self {
+        RelativePattern {
+            base,
+            pattern: pattern.into(),
+        }
+    }
+}
+
+/**
+ * A compiled VS Code glob pattern.
+ *
+ * Supported syntax:
+ *  - `*` to match zero or more characters in a path segment
+ *  - `?` to match on one character in a path segment
+ *  - `**` to match any number of path segments, including none
+ *  - `{}` to group conditions (e.g. `{*.html,*.txt}`)
+ *  - `[]` to declare a range of characters to match (e.g. `example.[0-9]`)
+ *  - `[!...]` to negate a range of characters to match (e.g. `example.[!0-9]`)
+ *
+ * Both '/' and '\' are accepted as path separators in matched paths.
+ */
+#[derive(Debug, Clone)]
+pub struct Glob {
+    pattern: String,
+    base: Option<URI>,
+    options: GlobOptions,
+    // `None` for the empty pattern, which matches nothing
+    regex: Option<Regex>,
+}
+
+impl Glob {
+    pub fn new(pattern: &str) -> Result<Self, UriError> {
+        Self::with_options(pattern, GlobOptions::default())
+    }
+
+    pub fn with_options(pattern: &str, options: GlobOptions) -> Result<Self, UriError> {
+        Self::compile(pattern, None, options)
+    }
+
+    pub fn relative(pattern: &RelativePattern) -> Result<Self, UriError> {
+        Self::relative_with_options(pattern, GlobOptions::default())
+    }
+
+    pub fn relative_with_options(
+        pattern: &RelativePattern,
+        options: GlobOptions,
+    ) -> Result<Self, UriError> {
+        Self::compile(&pattern.pattern, Some(pattern.base.clone()), options)
+    }
+
+    fn compile(pattern: &str, base: Option<URI>, options: GlobOptions) -> Result<Self, UriError> {
+        let pattern = pattern.trim();
+        let regex = if pattern.is_empty() {
+            None
+        } else {
+            let regex = RegexBuilder::new(&format!("^(?:{})$", parse_reg_exp(pattern)))
+                .case_insensitive(options.ignore_case)
+                .build()
+                .map_err(|_| UriError::InvalidGlobPattern {
+                    context: Box::new(ErrorContext {
+                        input: pattern.to_string(),
+                        span: 0..pattern.len(),
+                        component: UriComponent::Path,
+                    }),
+                })?;
+            Some(regex)
+        };
+
+        Ok(Glob {
+            pattern: pattern.to_string(),
+            base,
+            options,
+            regex,
+        })
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn base(&self) -> Option<&URI> {
+        self.base.as_ref()
+    }
+
+    /**
+     * Matches a path. For relative patterns, the path must be at or below the path of the base URI.
+     */
+    pub fn is_match_path(&self, path: &str) -> bool {
+        let regex = match &self.regex {
+            Some(regex) => regex,
+            None => return false,
+        };
+        match &self.base {
+            Some(base) => match self.relative_path(base.path(), path) {
+                Some(relative) => regex.is_match(relative),
+                None => false,
+            },
+            None => regex.is_match(path),
+        }
+    }
+
+    /**
+     * Matches the path of a URI. For relative patterns, the URI must also have the scheme and
+     * authority of the base URI.
+     */
+    pub fn is_match(&self, uri: &URI) -> bool {
+        if let Some(base) = &self.base {
+            if base.scheme() != uri.scheme()
+                || !base.authority().eq_ignore_ascii_case(uri.authority())
+            {
+                return false;
+            }
+        }
+        self.is_match_path(uri.path())
+    }
+
+    // Returns the part of `path` below `base`, without leading separators.
+    fn relative_path<'a>(&self, base: &str, path: &'a str) -> Option<&'a str> {
+        if path.len() < base.len() || !path.is_char_boundary(base.len()) {
+            return None;
+        }
+        let (prefix, rest) = path.split_at(base.len());
+        let is_prefix = if self.options.ignore_case {
+            prefix.to_lowercase() == base.to_lowercase()
+        } else {
+            prefix == base
+        };
+        if !is_prefix {
+            return None;
+        }
+        if !rest.is_empty() && !base.ends_with(['/', '\\']) && !rest.starts_with(['/', '\\']) {
+            return None;
+        }
+        Some(rest.trim_start_matches(['/', '\\']))
+    }
+}
+
+fn stars_to_reg_exp(star_count: usize, is_last_pattern: bool) -> String {
+    match star_count {
+        0 => String::new(),
+        1 => format!("{}*?", NO_PATH_REGEX),
+        // Matches: (Path Sep OR Path Val followed by Path Sep) 0-many times except when it's
+        // the last pattern, then also (Path Sep followed by Path Val)
+        _ => format!(
+            "(?:{}|{}+{}{})*?",
+            PATH_REGEX,
+            NO_PATH_REGEX,
+            PATH_REGEX,
+            if is_last_pattern {
+                format!("|{}{}+", PATH_REGEX, NO_PATH_REGEX)
+            } else {
+                String::new()
+            }
+        ),
+    }
+}
+
+// Splits on `split_char` unless it is inside of `{}` or `[]`.
+fn split_glob_aware(pattern: &str, split_char: char) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut in_braces = false;
+    let mut in_brackets = false;
+    let mut cur_val = String::new();
+    for c in pattern.chars() {
+        match c {
+            c if c == split_char && !in_braces && !in_brackets => {
+                segments.push(core::mem::take(&mut cur_val));
+                continue;
+            }
+            '{' => in_braces = true,
+            '}' => in_braces = false,
+            '[' => in_brackets = true,
+            ']' => in_brackets = false,
+            _ => {}
+        }
+        cur_val.push(c);
+    }
+    if !cur_val.is_empty() {
+        segments.push(cur_val);
+    }
+    segments
+}
+
+fn parse_reg_exp(pattern: &str) -> String {
+    let mut reg_ex = String::new();
+
+    let segments = split_glob_aware(pattern, GLOB_SPLIT);
+    if segments.iter().all(|segment| segment == GLOBSTAR) {
+        return ".*".to_string();
+    }
+
+    let mut previous_segment_was_glob_star = false;
+    for (index, segment) in segments.iter().enumerate() {
+        if segment == GLOBSTAR {
+            // Ignore consecutive globstars
+            if !previous_segment_was_glob_star {
+                reg_ex.push_str(&stars_to_reg_exp(2, index == segments.len() - 1));
+                previous_segment_was_glob_star = true;
+            }
+            continue;
+        }
+
+        let mut in_braces = false;
+        let mut brace_val = String::new();
+        let mut in_brackets = false;
+        let mut bracket_val = String::new();
+
+        for c in segment.chars() {
+            // Support brace expansion
+            if c != '}' && in_braces {
+                brace_val.push(c);
+                continue;
+            }
+
+            // Support brackets; a ']' right after '[' is taken literally
+            if in_brackets && (c != ']' || bracket_val.is_empty()) {
+                if c == '-' {
+                    // range operator
+                    bracket_val.push(c);
+                } else if (c == '^' || c == '!') && bracket_val.is_empty() {
+                    // negation operator (only valid on first index in bracket)
+                    bracket_val.push('^');
+                } else if c != GLOB_SPLIT {
+                    // no path separator in brackets
+                    bracket_val.push_str(&regex::escape(&c.to_string()));
+                }
+                continue;
+            }
+
+            match c {
+                '{' => in_braces = true,
+                '[' => in_brackets = true,
+                '}' => {
+                    let choices: Vec<String> = split_glob_aware(&brace_val, ',')
+                        .iter()
+                        .map(|choice| parse_reg_exp(choice))
+                        .collect();
+                    reg_ex.push_str(&format!("(?:{})", choices.join("|")));
+                    in_braces = false;
+                    brace_val.clear();
+                }
+                ']' => {
+                    reg_ex.push_str(&format!("[{}]", bracket_val));
+                    in_brackets = false;
+                    bracket_val.clear();
+                }
+                '?' => reg_ex.push_str(NO_PATH_REGEX),
+                '*' => reg_ex.push_str(&stars_to_reg_exp(1, false)),
+                c => reg_ex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+
+        // Tail: Add the slash we had split on if there is more to come and the remaining
+        // pattern is not a globstar. For example if pattern: some/**/*.js we want the "/"
+        // after some to be included in the RegEx to prevent a folder called "something"
+        // to match as well.
+        if index < segments.len() - 1
+            && (segments[index + 1] != GLOBSTAR || index + 2 < segments.len())
+        {
+            reg_ex.push_str(PATH_REGEX);
+        }
+
+        previous_segment_was_glob_star = false;
+    }
+
+    reg_ex
+}
@@ -3,11 +3,44 @@
  * https://github.com/microsoft/vscode-uri
  */
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(not(any(feature = "std", feature = "spin")))]
+compile_error!("without the `std` feature, enable the `spin` feature for the scheme registry");
+
 mod char_code;
+mod encoding;
+#[cfg(feature = "std")]
+mod git;
+pub mod glob;
+mod notebook;
+pub mod path;
+#[cfg(feature = "std")]
 pub mod platform;
+mod reference;
+pub mod remote;
+mod schemas;
+#[cfg(feature = "std")]
+pub mod selector;
+mod untitled;
 mod uri;
 mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
+pub use encoding::{decode_percent, decode_percent_bytes, EncodeOptions, EncodingSet};
+#[cfg(feature = "std")]
+pub use git::{GitUri, GitUriOptions, GitUriParams};
+pub use notebook::CellUri;
+#[cfg(feature = "std")]
 pub use platform::is_windows;
-pub use uri::{URIChange, URIComponents, UriError, URI};
-pub use utils::Utils;
+pub use reference::{RelativeRef, UriReference};
+pub use schemas::{Schemas, SchemeRegistry, SchemeRules};
+pub use untitled::UntitledUri;
+pub use uri::{
+    ErrorContext, FileOptions, ParseOptions, URIChange, URIComponents, UriComponent, UriError,
+    UriInterpretation, URI,
+};
+pub use utils::{ExtnameOptions, Utils};
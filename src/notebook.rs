@@ -0,0 +1,141 @@
+/*
+ * Rust implementation of vscode-uri
+ * https://github.com/microsoft/vscode-uri
+ */
+
+use crate::schemas::Schemas;
+use crate::uri::{URIChange, UriError, URI};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+const LENGTHS: [char; 10] = ['W', 'X', 'Y', 'Z', 'a', 'b', 'c', 'd', 'e', 'f'];
+const RADIX: u32 = 7;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/**
+ * Helpers for `vscode-notebook-cell:` URIs, which identify a cell of a notebook document.
+ *
+ * The cell URI is the notebook URI with the scheme replaced and a fragment of the form
+ * `<length marker><handle in base 7>s<base64url of the notebook scheme>`, e.g.
+ * `vscode-notebook-cell:/nb.ipynb#W0sZmlsZQ==` for cell 0 of `file:///nb.ipynb`.
+ */
+pub struct CellUri;
+
+impl CellUri {
+    pub const SCHEME: &'static str = Schemas::VSCODE_NOTEBOOK_CELL;
+
+    /**
+     * Returns the URI of the cell with the given handle in `notebook`.
+     */
+    pub fn generate(notebook: &URI, handle: u32) -> Result<URI, UriError> {
+        let s = to_radix(handle, RADIX);
+        let p = if s.len() < LENGTHS.len() {
+            LENGTHS[s.len() - 1]
+        } else {
+            'z'
+        };
+        let fragment = format!("{}{}s{}", p, s, encode_base64(notebook.scheme().as_bytes()));
+
+        notebook.with(URIChange {
+            scheme: Some(Self::SCHEME.to_string()),
+            fragment: Some(fragment),
+            ..Default::default()
+        })
+    }
+
+    /**
+     * Returns the notebook URI and the cell handle encoded in a cell URI,
+     * or `None` if `cell` is not a well-formed `vscode-notebook-cell:` URI.
+     */
+    pub fn parse(cell: &URI) -> Option<(URI, u32)> {
+        if cell.scheme() != Self::SCHEME {
+            return None;
+        }
+
+        let (handle, scheme) = cell.fragment().split_once('s')?;
+        // VS Code only strips the `LENGTHS` markers, which makes handles of 7^9 and above
+        // unparseable; the 'z' marker it writes for those is stripped as well here.
+        let handle = handle.trim_start_matches(LENGTHS).trim_start_matches('z');
+        let handle = parse_radix(handle, RADIX)?;
+        let scheme = String::from_utf8(decode_base64(scheme)?).ok()?;
+
+        let notebook = cell
+            .with(URIChange {
+                scheme: Some(scheme),
+                fragment: Some(String::new()),
+                ..Default::default()
+            })
+            .ok()?;
+        Some((notebook, handle))
+    }
+}
+
+fn to_radix(mut value: u32, radix: u32) -> String {
+    let mut digits = Vec::new();
+    loop {
+        digits.push(core::char::from_digit(value % radix, radix).unwrap());
+        value /= radix;
+        if value == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
+// Like JavaScript's `parseInt`, parses the leading digits and ignores the rest.
+fn parse_radix(value: &str, radix: u32) -> Option<u32> {
+    let end = value
+        .find(|c: char| !c.is_digit(radix))
+        .unwrap_or(value.len());
+    if end == 0 {
+        return None;
+    }
+    u32::from_str_radix(&value[..end], radix).ok()
+}
+
+// URL-safe alphabet with padding, as VS Code's `encodeBase64(buffer, true, true)`.
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut res = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                res.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                res.push('=');
+            }
+        }
+    }
+    res
+}
+
+// Accepts both the standard and the URL-safe alphabet, with or without padding.
+fn decode_base64(value: &str) -> Option<Vec<u8>> {
+    let value = value.trim_end_matches('=');
+    let mut res = Vec::with_capacity(value.len() * 3 / 4);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in value.bytes() {
+        let sextet = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        buffer = buffer << 6 | sextet as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            res.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(res)
+}
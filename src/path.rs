@@ -0,0 +1,1211 @@
+/*
+ * Rust implementation of vscode-uri
+ * https://github.com/microsoft/vscode-uri
+ */
+
+//! A port of Node's `path` module with its `posix` and `win32` flavors, like
+//! `src/vs/base/common/path.ts` in VS Code.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+const WINDOWS_RESERVED_NAMES: [&str; 28] = [
+    "CON",
+    "PRN",
+    "AUX",
+    "NUL",
+    "COM1",
+    "COM2",
+    "COM3",
+    "COM4",
+    "COM5",
+    "COM6",
+    "COM7",
+    "COM8",
+    "COM9",
+    "LPT1",
+    "LPT2",
+    "LPT3",
+    "LPT4",
+    "LPT5",
+    "LPT6",
+    "LPT7",
+    "LPT8",
+    "LPT9",
+    "COM\u{b9}",
+    "COM\u{b2}",
+    "COM\u{b3}",
+    "LPT\u{b9}",
+    "LPT\u{b2}",
+    "LPT\u{b3}",
+];
+
+/**
+ * The result of `parse`, and the input of `format`.
+ */
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParsedPath {
+    pub root: String,
+    pub dir: String,
+    pub base: String,
+    pub ext: String,
+    pub name: String,
+}
+
+/**
+ * `path.posix`: paths separated by '/'.
+ */
+pub struct Posix;
+
+/**
+ * `path.win32`: paths separated by '\' or '/', with drive letters (`C:\`) and UNC roots
+ * (`\\server\share\`). Results use '\'.
+ */
+pub struct Win32;
+
+fn is_path_separator(code: u8) -> bool {
+    code == b'/' || code == b'\\'
+}
+
+fn is_posix_path_separator(code: u8) -> bool {
+    code == b'/'
+}
+
+fn is_windows_device_root(code: u8) -> bool {
+    code.is_ascii_alphabetic()
+}
+
+// Like `charCodeAt`, but with 0 (which is neither a separator, '.' nor ':') past the end.
+fn code_at(path: &str, idx: usize) -> u8 {
+    path.as_bytes().get(idx).copied().unwrap_or(0)
+}
+
+// Whether the part of `path` before `colon_index` (or before its last character if there is no
+// colon, as in Node) is a reserved device name such as `CON` or `COM1`.
+fn is_windows_reserved_name(path: &str, colon_index: Option<usize>) -> bool {
+    let end = colon_index.unwrap_or_else(|| path.char_indices().last().map_or(0, |(idx, _)| idx));
+    let device_part = path[..end].to_uppercase();
+    WINDOWS_RESERVED_NAMES.contains(&device_part.as_str())
+}
+
+// Matches the `\\server\share` at the start of a path that starts with two separators.
+// Returns the end of the server name and the range of the share name.
+fn match_unc_root(path: &str) -> Option<(usize, usize, usize)> {
+    let bytes = path.as_bytes();
+    let len = bytes.len();
+    let mut j = 2;
+    while j < len && !is_path_separator(bytes[j]) {
+        j += 1;
+    }
+    if j == len || j == 2 {
+        return None;
+    }
+    let server_end = j;
+    while j < len && is_path_separator(bytes[j]) {
+        j += 1;
+    }
+    if j == len || j == server_end {
+        return None;
+    }
+    let share_start = j;
+    while j < len && !is_path_separator(bytes[j]) {
+        j += 1;
+    }
+    Some((server_end, share_start, j))
+}
+
+// Resolves '.' and '..' elements in a path with directory names.
+fn normalize_string(
+    path: &str,
+    allow_above_root: bool,
+    separator: char,
+    is_path_separator: fn(u8) -> bool,
+) -> String {
+    let bytes = path.as_bytes();
+    let mut res = String::new();
+    let mut last_segment_length = 0;
+    let mut last_slash: isize = -1;
+    let mut dots = 0;
+    let mut code = 0;
+    for i in 0..=bytes.len() {
+        if i < bytes.len() {
+            code = bytes[i];
+        } else if is_path_separator(code) {
+            break;
+        } else {
+            code = b'/';
+        }
+
+        if is_path_separator(code) {
+            if last_slash == i as isize - 1 || dots == 1 {
+                // NOOP
+            } else if dots == 2 {
+                if res.len() < 2 || last_segment_length != 2 || !res.ends_with("..") {
+                    if res.len() > 2 {
+                        match res.rfind(separator) {
+                            Some(last_slash_index) => {
+                                res.truncate(last_slash_index);
+                                last_segment_length =
+                                    res.len() - res.rfind(separator).map_or(0, |idx| idx + 1);
+                            }
+                            None => {
+                                res.clear();
+                                last_segment_length = 0;
+                            }
+                        }
+                        last_slash = i as isize;
+                        dots = 0;
+                        continue;
+                    } else if !res.is_empty() {
+                        res.clear();
+                        last_segment_length = 0;
+                        last_slash = i as isize;
+                        dots = 0;
+                        continue;
+                    }
+                }
+                if allow_above_root {
+                    if !res.is_empty() {
+                        res.push(separator);
+                    }
+                    res.push_str("..");
+                    last_segment_length = 2;
+                }
+            } else {
+                let start = (last_slash + 1) as usize;
+                if !res.is_empty() {
+                    res.push(separator);
+                }
+                res.push_str(&path[start..i]);
+                last_segment_length = i - start;
+            }
+            last_slash = i as isize;
+            dots = 0;
+        } else if code == b'.' && dots != -1 {
+            dots += 1;
+        } else {
+            dots = -1;
+        }
+    }
+    res
+}
+
+fn format_path(sep: char, path_object: &ParsedPath) -> String {
+    let dir = if path_object.dir.is_empty() {
+        &path_object.root
+    } else {
+        &path_object.dir
+    };
+    let base = if path_object.base.is_empty() {
+        let ext = &path_object.ext;
+        let dot = if ext.is_empty() || ext.starts_with('.') {
+            ""
+        } else {
+            "."
+        };
+        format!("{}{}{}", path_object.name, dot, ext)
+    } else {
+        path_object.base.clone()
+    };
+    if dir.is_empty() {
+        base
+    } else if *dir == path_object.root {
+        format!("{}{}", dir, base)
+    } else {
+        format!("{}{}{}", dir, sep, base)
+    }
+}
+
+// Scans the last segment of `path[start..]`: returns the position of the dot of its extension
+// (`None` for `a`, `.a` or `..`), its start (`start_part` if it has no separator before it), and
+// its end.
+fn find_extension(
+    path: &str,
+    start: usize,
+    start_part: usize,
+    is_path_separator: fn(u8) -> bool,
+) -> (Option<usize>, usize, Option<usize>) {
+    let bytes = path.as_bytes();
+    let mut start_dot = None;
+    let mut start_part = start_part;
+    let mut end = None;
+    let mut matched_slash = true;
+    // the state of the characters (if any) before the first dot and after any path separator
+    let mut pre_dot_state = 0;
+    for i in (start..bytes.len()).rev() {
+        let code = bytes[i];
+        if is_path_separator(code) {
+            if !matched_slash {
+                start_part = i + 1;
+                break;
+            }
+            continue;
+        }
+        if end.is_none() {
+            matched_slash = false;
+            end = Some(i + 1);
+        }
+        if code == b'.' {
+            if start_dot.is_none() {
+                start_dot = Some(i);
+            } else if pre_dot_state != 1 {
+                pre_dot_state = 1;
+            }
+        } else if start_dot.is_some() {
+            pre_dot_state = -1;
+        }
+    }
+
+    let dot = match (start_dot, end) {
+        // a non-dot character immediately before the dot
+        _ if pre_dot_state == 0 => None,
+        // the (right-most) trimmed path component is exactly '..'
+        (Some(dot), Some(end)) if pre_dot_state == 1 && dot == end - 1 && dot == start_part + 1 => {
+            None
+        }
+        (dot, _) => dot,
+    };
+    (dot, start_part, end)
+}
+
+fn basename_impl(
+    path: &str,
+    suffix: Option<&str>,
+    start: usize,
+    is_path_separator: fn(u8) -> bool,
+) -> String {
+    let bytes = path.as_bytes();
+    let mut start = start;
+    let mut matched_slash = true;
+
+    if let Some(suffix) = suffix.filter(|suffix| !suffix.is_empty() && suffix.len() <= path.len()) {
+        if suffix == path {
+            return String::new();
+        }
+        let suffix = suffix.as_bytes();
+        let mut ext_idx = suffix.len() as isize - 1;
+        let mut end = None;
+        let mut first_non_slash_end = None;
+        for i in (start..bytes.len()).rev() {
+            let code = bytes[i];
+            if is_path_separator(code) {
+                if !matched_slash {
+                    start = i + 1;
+                    break;
+                }
+            } else {
+                if first_non_slash_end.is_none() {
+                    matched_slash = false;
+                    first_non_slash_end = Some(i + 1);
+                }
+                if ext_idx >= 0 {
+                    if code == suffix[ext_idx as usize] {
+                        ext_idx -= 1;
+                        if ext_idx == -1 {
+                            end = Some(i);
+                        }
+                    } else {
+                        ext_idx = -1;
+                        end = first_non_slash_end;
+                    }
+                }
+            }
+        }
+
+        let end = match end {
+            Some(end) if end == start => first_non_slash_end.unwrap_or(bytes.len()),
+            Some(end) => end,
+            None => bytes.len(),
+        };
+        return path[start..end].to_string();
+    }
+
+    let mut end = None;
+    for i in (start..bytes.len()).rev() {
+        if is_path_separator(bytes[i]) {
+            if !matched_slash {
+                start = i + 1;
+                break;
+            }
+        } else if end.is_none() {
+            matched_slash = false;
+            end = Some(i + 1);
+        }
+    }
+    end.map_or(String::new(), |end| path[start..end].to_string())
+}
+
+// Whether lowercasing keeps every character of `path` at the same position. (Node checks the
+// length of the whole string, but positions in the lowercased path are used to slice the
+// original one here.)
+fn same_len_lowercase(path: &str) -> bool {
+    path.chars()
+        .all(|c| c.to_lowercase().map(char::len_utf8).sum::<usize>() == c.len_utf8())
+}
+
+#[cfg(feature = "std")]
+fn process_cwd() -> String {
+    std::env::current_dir()
+        .map(|cwd| cwd.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "/".to_string())
+}
+
+// Without `std`, there is no working directory; like in VS Code on the web, it is the root.
+#[cfg(not(feature = "std"))]
+fn process_cwd() -> String {
+    "/".to_string()
+}
+
+// The working directory of a drive (`=C:` in the environment), if the shell set it.
+#[cfg(feature = "std")]
+fn drive_cwd(device: &str) -> Option<String> {
+    std::env::var(format!("={}", device))
+        .ok()
+        .filter(|cwd| !cwd.is_empty())
+}
+
+#[cfg(not(feature = "std"))]
+fn drive_cwd(_device: &str) -> Option<String> {
+    None
+}
+
+fn posix_cwd() -> String {
+    let cwd = process_cwd();
+    if cfg!(windows) {
+        let cwd = cwd.replace('\\', "/");
+        return cwd[cwd.find('/').unwrap_or(0)..].to_string();
+    }
+    cwd
+}
+
+impl Posix {
+    pub const SEP: &'static str = "/";
+    pub const DELIMITER: &'static str = ":";
+
+    /**
+     * Resolves a sequence of paths to an absolute path, from right to left, using the working
+     * directory of the process if the paths are relative.
+     */
+    pub fn resolve(paths: &[&str]) -> String {
+        let mut resolved_path = String::new();
+        let mut resolved_absolute = false;
+
+        for path in paths.iter().rev() {
+            if path.is_empty() {
+                continue;
+            }
+            resolved_path = format!("{}/{}", path, resolved_path);
+            resolved_absolute = path.starts_with('/');
+            if resolved_absolute {
+                break;
+            }
+        }
+
+        if !resolved_absolute {
+            let cwd = posix_cwd();
+            resolved_path = format!("{}/{}", cwd, resolved_path);
+            resolved_absolute = cwd.starts_with('/');
+        }
+
+        let resolved_path = normalize_string(
+            &resolved_path,
+            !resolved_absolute,
+            '/',
+            is_posix_path_separator,
+        );
+
+        if resolved_absolute {
+            format!("/{}", resolved_path)
+        } else if resolved_path.is_empty() {
+            ".".to_string()
+        } else {
+            resolved_path
+        }
+    }
+
+    pub fn normalize(path: &str) -> String {
+        if path.is_empty() {
+            return ".".to_string();
+        }
+
+        let is_absolute = path.starts_with('/');
+        let trailing_separator = path.ends_with('/');
+
+        let mut path = normalize_string(path, !is_absolute, '/', is_posix_path_separator);
+
+        if path.is_empty() {
+            if is_absolute {
+                return "/".to_string();
+            }
+            return if trailing_separator { "./" } else { "." }.to_string();
+        }
+        if trailing_separator {
+            path.push('/');
+        }
+
+        if is_absolute {
+            format!("/{}", path)
+        } else {
+            path
+        }
+    }
+
+    pub fn is_absolute(path: &str) -> bool {
+        path.starts_with('/')
+    }
+
+    pub fn join(paths: &[&str]) -> String {
+        let paths: Vec<&str> = paths.iter().copied().filter(|p| !p.is_empty()).collect();
+        if paths.is_empty() {
+            return ".".to_string();
+        }
+        Self::normalize(&paths.join("/"))
+    }
+
+    /**
+     * Returns the relative path from `from` to `to`, after resolving both.
+     */
+    pub fn relative(from: &str, to: &str) -> String {
+        if from == to {
+            return String::new();
+        }
+
+        let from = Self::resolve(&[from]);
+        let to = Self::resolve(&[to]);
+
+        if from == to {
+            return String::new();
+        }
+
+        let from_start = 1;
+        let from_end = from.len();
+        let from_len = from_end - from_start;
+        let to_start = 1;
+        let to_len = to.len() - to_start;
+
+        let length = from_len.min(to_len);
+        let mut last_common_sep: isize = -1;
+        let mut i = 0;
+        while i < length {
+            let from_code = code_at(&from, from_start + i);
+            if from_code != code_at(&to, to_start + i) {
+                break;
+            } else if from_code == b'/' {
+                last_common_sep = i as isize;
+            }
+            i += 1;
+        }
+        if i == length {
+            if to_len > length {
+                if code_at(&to, to_start + i) == b'/' {
+                    // `from` is the exact base path for `to`, e.g. `/foo/bar` and `/foo/bar/baz`
+                    return to[to_start + i + 1..].to_string();
+                }
+                if i == 0 {
+                    // `from` is the root
+                    return to[to_start + i..].to_string();
+                }
+            } else if from_len > length {
+                if code_at(&from, from_start + i) == b'/' {
+                    // `to` is the exact base path for `from`, e.g. `/foo/bar/baz` and `/foo/bar`
+                    last_common_sep = i as isize;
+                } else if i == 0 {
+                    // `to` is the root
+                    last_common_sep = 0;
+                }
+            }
+        }
+
+        let mut out = String::new();
+        // a '..' for each segment of `from` after the common path
+        for i in (from_start as isize + last_common_sep + 1) as usize..=from_end {
+            if i == from_end || code_at(&from, i) == b'/' {
+                out.push_str(if out.is_empty() { ".." } else { "/.." });
+            }
+        }
+
+        format!(
+            "{}{}",
+            out,
+            &to[(to_start as isize + last_common_sep) as usize..]
+        )
+    }
+
+    /**
+     * Returns `path` unchanged; namespaced paths only exist on Windows.
+     */
+    pub fn to_namespaced_path(path: &str) -> String {
+        path.to_string()
+    }
+
+    pub fn dirname(path: &str) -> String {
+        if path.is_empty() {
+            return ".".to_string();
+        }
+        let bytes = path.as_bytes();
+        let has_root = bytes[0] == b'/';
+        let mut end = None;
+        let mut matched_slash = true;
+        for i in (1..bytes.len()).rev() {
+            if bytes[i] == b'/' {
+                if !matched_slash {
+                    end = Some(i);
+                    break;
+                }
+            } else {
+                matched_slash = false;
+            }
+        }
+
+        match end {
+            None if has_root => "/".to_string(),
+            None => ".".to_string(),
+            Some(1) if has_root => "//".to_string(),
+            Some(end) => path[..end].to_string(),
+        }
+    }
+
+    /**
+     * Returns the last segment of `path`, without `suffix` if it ends with it.
+     */
+    pub fn basename(path: &str, suffix: Option<&str>) -> String {
+        basename_impl(path, suffix, 0, is_posix_path_separator)
+    }
+
+    pub fn extname(path: &str) -> String {
+        match find_extension(path, 0, 0, is_posix_path_separator) {
+            (Some(dot), _, Some(end)) => path[dot..end].to_string(),
+            _ => String::new(),
+        }
+    }
+
+    pub fn format(path_object: &ParsedPath) -> String {
+        format_path('/', path_object)
+    }
+
+    pub fn parse(path: &str) -> ParsedPath {
+        let mut ret = ParsedPath::default();
+        if path.is_empty() {
+            return ret;
+        }
+        let is_absolute = path.starts_with('/');
+        let start = if is_absolute {
+            ret.root = "/".to_string();
+            1
+        } else {
+            0
+        };
+
+        let (dot, start_part, end) = find_extension(path, start, 0, is_posix_path_separator);
+        if let Some(end) = end {
+            let start = if start_part == 0 && is_absolute {
+                1
+            } else {
+                start_part
+            };
+            match dot {
+                Some(dot) => {
+                    ret.name = path[start..dot].to_string();
+                    ret.base = path[start..end].to_string();
+                    ret.ext = path[dot..end].to_string();
+                }
+                None => {
+                    ret.base = path[start..end].to_string();
+                    ret.name = ret.base.clone();
+                }
+            }
+        }
+
+        if start_part > 0 {
+            ret.dir = path[..start_part - 1].to_string();
+        } else if is_absolute {
+            ret.dir = "/".to_string();
+        }
+        ret
+    }
+}
+
+impl Win32 {
+    pub const SEP: &'static str = "\\";
+    pub const DELIMITER: &'static str = ";";
+
+    /**
+     * Resolves a sequence of paths to an absolute path, from right to left, using the working
+     * directory of the process (or of the drive, if set in the environment) if needed.
+     */
+    pub fn resolve(paths: &[&str]) -> String {
+        let mut resolved_device = String::new();
+        let mut resolved_tail = String::new();
+        let mut resolved_absolute = false;
+
+        for i in (-1..paths.len() as isize).rev() {
+            let path = if i >= 0 {
+                let path = paths[i as usize];
+                if path.is_empty() {
+                    continue;
+                }
+                path.to_string()
+            } else if resolved_device.is_empty() {
+                process_cwd()
+            } else {
+                // Windows has a working directory per drive. The device is not a UNC root here,
+                // because UNC paths are always absolute.
+                let path = drive_cwd(&resolved_device).unwrap_or_else(process_cwd);
+                // fall back to the root of the drive if the working directory is on another one
+                if path.get(..2).map(str::to_lowercase) != Some(resolved_device.to_lowercase())
+                    && code_at(&path, 2) == b'\\'
+                {
+                    format!("{}\\", resolved_device)
+                } else {
+                    path
+                }
+            };
+
+            let bytes = path.as_bytes();
+            let len = bytes.len();
+            let mut root_end = 0;
+            let mut device = String::new();
+            let mut is_absolute = false;
+            let code = bytes[0];
+
+            if len == 1 {
+                if is_path_separator(code) {
+                    root_end = 1;
+                    is_absolute = true;
+                }
+            } else if is_path_separator(code) {
+                is_absolute = true;
+                if is_path_separator(bytes[1]) {
+                    if let Some((server_end, share_start, share_end)) = match_unc_root(&path) {
+                        let first_part = &path[2..server_end];
+                        if first_part != "." && first_part != "?" {
+                            device =
+                                format!("\\\\{}\\{}", first_part, &path[share_start..share_end]);
+                            root_end = share_end;
+                        } else {
+                            // a device root, e.g. `\\.\PHYSICALDRIVE0`
+                            device = format!("\\\\{}", first_part);
+                            root_end = 4;
+                        }
+                    }
+                } else {
+                    root_end = 1;
+                }
+            } else if is_windows_device_root(code) && bytes[1] == b':' {
+                device = path[..2].to_string();
+                root_end = 2;
+                if len > 2 && is_path_separator(bytes[2]) {
+                    // a separator after the drive name makes the path absolute
+                    is_absolute = true;
+                    root_end = 3;
+                }
+            }
+
+            if !device.is_empty() {
+                if !resolved_device.is_empty() {
+                    if device.to_lowercase() != resolved_device.to_lowercase() {
+                        // this path is on another device
+                        continue;
+                    }
+                } else {
+                    resolved_device = device;
+                }
+            }
+
+            if resolved_absolute {
+                if !resolved_device.is_empty() {
+                    break;
+                }
+            } else {
+                resolved_tail = format!("{}\\{}", &path[root_end..], resolved_tail);
+                resolved_absolute = is_absolute;
+                if is_absolute && !resolved_device.is_empty() {
+                    break;
+                }
+            }
+        }
+
+        let resolved_tail =
+            normalize_string(&resolved_tail, !resolved_absolute, '\\', is_path_separator);
+
+        if resolved_absolute {
+            format!("{}\\{}", resolved_device, resolved_tail)
+        } else if resolved_device.is_empty() && resolved_tail.is_empty() {
+            ".".to_string()
+        } else {
+            format!("{}{}", resolved_device, resolved_tail)
+        }
+    }
+
+    pub fn normalize(path: &str) -> String {
+        let bytes = path.as_bytes();
+        let len = bytes.len();
+        if len == 0 {
+            return ".".to_string();
+        }
+        let mut root_end = 0;
+        let mut device: Option<String> = None;
+        let mut is_absolute = false;
+        let code = bytes[0];
+
+        if len == 1 {
+            return if is_posix_path_separator(code) {
+                "\\".to_string()
+            } else {
+                path.to_string()
+            };
+        }
+        if is_path_separator(code) {
+            is_absolute = true;
+            if is_path_separator(bytes[1]) {
+                if let Some((server_end, share_start, share_end)) = match_unc_root(path) {
+                    let first_part = &path[2..server_end];
+                    if first_part == "." || first_part == "?" {
+                        // a device root, e.g. `\\.\PHYSICALDRIVE0`
+                        device = Some(format!("\\\\{}", first_part));
+                        root_end = 4;
+                    } else if share_end == len {
+                        // only a UNC root
+                        return format!("\\\\{}\\{}\\", first_part, &path[share_start..]);
+                    } else {
+                        device = Some(format!(
+                            "\\\\{}\\{}",
+                            first_part,
+                            &path[share_start..share_end]
+                        ));
+                        root_end = share_end;
+                    }
+                }
+            } else {
+                root_end = 1;
+            }
+        } else if let Some(colon_index) = path.find(':').filter(|idx| *idx > 0) {
+            if is_windows_device_root(code) && colon_index == 1 {
+                device = Some(path[..2].to_string());
+                root_end = 2;
+                if len > 2 && is_path_separator(bytes[2]) {
+                    is_absolute = true;
+                    root_end = 3;
+                }
+            } else if is_windows_reserved_name(path, Some(colon_index)) {
+                device = Some(path[..=colon_index].to_string());
+                root_end = colon_index + 1;
+            }
+        }
+
+        let mut tail = if root_end < len {
+            normalize_string(&path[root_end..], !is_absolute, '\\', is_path_separator)
+        } else {
+            String::new()
+        };
+        if tail.is_empty() && !is_absolute {
+            tail = ".".to_string();
+        }
+        if !tail.is_empty() && is_path_separator(bytes[len - 1]) {
+            tail.push('\\');
+        }
+        if !is_absolute && device.is_none() && path.contains(':') {
+            // Make sure that a relative path does not become something that Windows takes for
+            // an absolute path (CVE-2024-36139).
+            let tail_bytes = tail.as_bytes();
+            if tail_bytes.len() >= 2
+                && is_windows_device_root(tail_bytes[0])
+                && tail_bytes[1] == b':'
+            {
+                return format!(".\\{}", tail);
+            }
+            for (index, _) in path.match_indices(':') {
+                if index == len - 1 || is_path_separator(bytes[index + 1]) {
+                    return format!(".\\{}", tail);
+                }
+            }
+        }
+        if is_windows_reserved_name(path, path.find(':')) {
+            return format!(".\\{}{}", device.unwrap_or_default(), tail);
+        }
+        match device {
+            None if is_absolute => format!("\\{}", tail),
+            None => tail,
+            Some(device) if is_absolute => format!("{}\\{}", device, tail),
+            Some(device) => format!("{}{}", device, tail),
+        }
+    }
+
+    pub fn is_absolute(path: &str) -> bool {
+        let bytes = path.as_bytes();
+        match bytes.first() {
+            None => false,
+            Some(code) => {
+                is_path_separator(*code)
+                    || (bytes.len() > 2
+                        && is_windows_device_root(*code)
+                        && bytes[1] == b':'
+                        && is_path_separator(bytes[2]))
+            }
+        }
+    }
+
+    pub fn join(paths: &[&str]) -> String {
+        let paths: Vec<&str> = paths.iter().copied().filter(|p| !p.is_empty()).collect();
+        let Some(first_part) = paths.first().map(|p| p.as_bytes()) else {
+            return ".".to_string();
+        };
+        let mut joined = paths.join("\\");
+
+        // Make sure that the joined path does not start with two separators, which `normalize`
+        // would take for a UNC root, unless the first part clearly is one (it starts with
+        // exactly two separators followed by something else), e.g. `join(["//server", "share"])`.
+        let mut needs_replace = true;
+        let mut slash_count = 0;
+        if is_path_separator(first_part[0]) {
+            slash_count += 1;
+            let first_len = first_part.len();
+            if first_len > 1 && is_path_separator(first_part[1]) {
+                slash_count += 1;
+                if first_len > 2 {
+                    if is_path_separator(first_part[2]) {
+                        slash_count += 1;
+                    } else {
+                        needs_replace = false;
+                    }
+                }
+            }
+        }
+        if needs_replace {
+            while slash_count < joined.len() && is_path_separator(joined.as_bytes()[slash_count]) {
+                slash_count += 1;
+            }
+            if slash_count >= 2 {
+                joined = format!("\\{}", &joined[slash_count..]);
+            }
+        }
+
+        Self::normalize(&joined)
+    }
+
+    /**
+     * Returns the relative path from `from` to `to`, after resolving both. Paths are compared
+     * case-insensitively.
+     */
+    pub fn relative(from: &str, to: &str) -> String {
+        if from == to {
+            return String::new();
+        }
+
+        let from_orig = Self::resolve(&[from]);
+        let to_orig = Self::resolve(&[to]);
+
+        if from_orig == to_orig {
+            return String::new();
+        }
+
+        let from = from_orig.to_lowercase();
+        let to = to_orig.to_lowercase();
+
+        if from == to {
+            return String::new();
+        }
+
+        if !same_len_lowercase(&from_orig) || !same_len_lowercase(&to_orig) {
+            // lowercasing moved characters, so compare the segments instead of the characters
+            let mut from_split: Vec<&str> = from_orig.split('\\').collect();
+            let mut to_split: Vec<&str> = to_orig.split('\\').collect();
+            if from_split.last() == Some(&"") {
+                from_split.pop();
+            }
+            if to_split.last() == Some(&"") {
+                to_split.pop();
+            }
+
+            let from_len = from_split.len();
+            let to_len = to_split.len();
+            let length = from_len.min(to_len);
+
+            let i = (0..length)
+                .find(|i| from_split[*i].to_lowercase() != to_split[*i].to_lowercase())
+                .unwrap_or(length);
+
+            if i == 0 {
+                return to_orig;
+            } else if i == length {
+                if to_len > length {
+                    return to_split[i..].join("\\");
+                }
+                if from_len > length {
+                    return format!("{}..", "..\\".repeat(from_len - 1 - i));
+                }
+                return String::new();
+            }
+
+            return format!(
+                "{}{}",
+                "..\\".repeat(from_len - i),
+                to_split[i..].join("\\")
+            );
+        }
+
+        // trim leading backslashes, and trailing ones (of UNC roots)
+        let trim = |path: &str| {
+            let bytes = path.as_bytes();
+            let mut start = 0;
+            while start < bytes.len() && bytes[start] == b'\\' {
+                start += 1;
+            }
+            let mut end = bytes.len();
+            while end > start + 1 && bytes[end - 1] == b'\\' {
+                end -= 1;
+            }
+            (start, end)
+        };
+        let (from_start, from_end) = trim(&from);
+        let from_len = from_end - from_start;
+        let (mut to_start, to_end) = trim(&to);
+        let to_len = to_end - to_start;
+
+        let length = from_len.min(to_len);
+        let mut last_common_sep: isize = -1;
+        let mut i = 0;
+        while i < length {
+            let from_code = code_at(&from, from_start + i);
+            if from_code != code_at(&to, to_start + i) {
+                break;
+            } else if from_code == b'\\' {
+                last_common_sep = i as isize;
+            }
+            i += 1;
+        }
+
+        if i != length {
+            // a mismatch before the first common separator
+            if last_common_sep == -1 {
+                return to_orig;
+            }
+        } else {
+            if to_len > length {
+                if code_at(&to, to_start + i) == b'\\' {
+                    // `from` is the exact base path for `to`, e.g. `C:\foo\bar` and `C:\foo\bar\baz`
+                    return to_orig[to_start + i + 1..].to_string();
+                }
+                if i == 2 {
+                    // `from` is the device root, e.g. `C:\` and `C:\foo`
+                    return to_orig[to_start + i..].to_string();
+                }
+            }
+            if from_len > length {
+                if code_at(&from, from_start + i) == b'\\' {
+                    // `to` is the exact base path for `from`, e.g. `C:\foo\bar` and `C:\foo`
+                    last_common_sep = i as isize;
+                } else if i == 2 {
+                    // `to` is the device root, e.g. `C:\foo\bar` and `C:\`
+                    last_common_sep = 3;
+                }
+            }
+            if last_common_sep == -1 {
+                last_common_sep = 0;
+            }
+        }
+
+        let mut out = String::new();
+        // a '..' for each segment of `from` after the common path
+        for i in (from_start as isize + last_common_sep + 1) as usize..=from_end {
+            if i == from_end || code_at(&from, i) == b'\\' {
+                out.push_str(if out.is_empty() { ".." } else { "\\.." });
+            }
+        }
+
+        to_start += last_common_sep as usize;
+
+        if !out.is_empty() {
+            return format!("{}{}", out, &to_orig[to_start..to_end]);
+        }
+        if code_at(&to_orig, to_start) == b'\\' {
+            to_start += 1;
+        }
+        to_orig[to_start..to_end].to_string()
+    }
+
+    /**
+     * Returns the long (`\\?\`) form of an absolute drive or UNC path, e.g. `\\?\C:\a` or
+     * `\\?\UNC\server\share`. Other paths are returned resolved, or as is if they are empty.
+     */
+    pub fn to_namespaced_path(path: &str) -> String {
+        if path.is_empty() {
+            return String::new();
+        }
+
+        let resolved_path = Self::resolve(&[path]);
+        if resolved_path.len() <= 2 {
+            return path.to_string();
+        }
+
+        let bytes = resolved_path.as_bytes();
+        if bytes[0] == b'\\' {
+            if bytes[1] == b'\\' && bytes[2] != b'?' && bytes[2] != b'.' {
+                // a UNC root
+                return format!("\\\\?\\UNC\\{}", &resolved_path[2..]);
+            }
+        } else if is_windows_device_root(bytes[0]) && bytes[1] == b':' && bytes[2] == b'\\' {
+            // a device root
+            return format!("\\\\?\\{}", resolved_path);
+        }
+        resolved_path
+    }
+
+    pub fn dirname(path: &str) -> String {
+        let bytes = path.as_bytes();
+        let len = bytes.len();
+        if len == 0 {
+            return ".".to_string();
+        }
+        let mut root_end = None;
+        let mut offset = 0;
+        let code = bytes[0];
+
+        if len == 1 {
+            return if is_path_separator(code) { path } else { "." }.to_string();
+        }
+
+        if is_path_separator(code) {
+            root_end = Some(1);
+            offset = 1;
+            if is_path_separator(bytes[1]) {
+                if let Some((_, _, share_end)) = match_unc_root(path) {
+                    if share_end == len {
+                        // only a UNC root
+                        return path.to_string();
+                    }
+                    // the separator after the UNC root belongs to the root
+                    root_end = Some(share_end + 1);
+                    offset = share_end + 1;
+                }
+            }
+        } else if is_windows_device_root(code) && bytes[1] == b':' {
+            let end = if len > 2 && is_path_separator(bytes[2]) {
+                3
+            } else {
+                2
+            };
+            root_end = Some(end);
+            offset = end;
+        }
+
+        let mut end = None;
+        let mut matched_slash = true;
+        for i in (offset..len).rev() {
+            if is_path_separator(bytes[i]) {
+                if !matched_slash {
+                    end = Some(i);
+                    break;
+                }
+            } else {
+                matched_slash = false;
+            }
+        }
+
+        match end.or(root_end) {
+            Some(end) => path[..end].to_string(),
+            None => ".".to_string(),
+        }
+    }
+
+    /**
+     * Returns the last segment of `path`, without `suffix` if it ends with it.
+     */
+    pub fn basename(path: &str, suffix: Option<&str>) -> String {
+        // the separator after a drive letter is not a trailing separator
+        let bytes = path.as_bytes();
+        let start = if bytes.len() >= 2 && is_windows_device_root(bytes[0]) && bytes[1] == b':' {
+            2
+        } else {
+            0
+        };
+        basename_impl(path, suffix, start, is_path_separator)
+    }
+
+    pub fn extname(path: &str) -> String {
+        let bytes = path.as_bytes();
+        let start = if bytes.len() >= 2 && bytes[1] == b':' && is_windows_device_root(bytes[0]) {
+            2
+        } else {
+            0
+        };
+        match find_extension(path, start, start, is_path_separator) {
+            (Some(dot), _, Some(end)) => path[dot..end].to_string(),
+            _ => String::new(),
+        }
+    }
+
+    pub fn format(path_object: &ParsedPath) -> String {
+        format_path('\\', path_object)
+    }
+
+    pub fn parse(path: &str) -> ParsedPath {
+        let mut ret = ParsedPath::default();
+        let bytes = path.as_bytes();
+        let len = bytes.len();
+        if len == 0 {
+            return ret;
+        }
+
+        let mut root_end = 0;
+        let code = bytes[0];
+
+        if len == 1 {
+            if is_path_separator(code) {
+                ret.root = path.to_string();
+                ret.dir = path.to_string();
+            } else {
+                ret.base = path.to_string();
+                ret.name = path.to_string();
+            }
+            return ret;
+        }
+        if is_path_separator(code) {
+            root_end = 1;
+            if is_path_separator(bytes[1]) {
+                if let Some((_, _, share_end)) = match_unc_root(path) {
+                    root_end = if share_end == len {
+                        share_end
+                    } else {
+                        share_end + 1
+                    };
+                }
+            }
+        } else if is_windows_device_root(code) && bytes[1] == b':' {
+            // only a drive root
+            if len <= 2 || (is_path_separator(bytes[2]) && len == 3) {
+                ret.root = path.to_string();
+                ret.dir = path.to_string();
+                return ret;
+            }
+            root_end = if is_path_separator(bytes[2]) { 3 } else { 2 };
+        }
+        if root_end > 0 {
+            ret.root = path[..root_end].to_string();
+        }
+
+        let (dot, start_part, end) = find_extension(path, root_end, root_end, is_path_separator);
+        if let Some(end) = end {
+            match dot {
+                Some(dot) => {
+                    ret.name = path[start_part..dot].to_string();
+                    ret.base = path[start_part..end].to_string();
+                    ret.ext = path[dot..end].to_string();
+                }
+                None => {
+                    ret.base = path[start_part..end].to_string();
+                    ret.name = ret.base.clone();
+                }
+            }
+        }
+
+        // the directory of `C:\abc` is the root, `C:\`, but the one of `C:\abc\def` is `C:\abc`
+        if start_part > 0 && start_part != root_end {
+            ret.dir = path[..start_part - 1].to_string();
+        } else {
+            ret.dir = ret.root.clone();
+        }
+        ret
+    }
+}
@@ -3,34 +3,107 @@
  * https://github.com/microsoft/vscode-uri
  */
 
+use std::cell::Cell;
+
 #[cfg(target_os = "windows")]
 pub const IS_WINDOWS: bool = true;
 
 #[cfg(not(target_os = "windows"))]
 pub const IS_WINDOWS: bool = false;
 
-pub fn is_windows() -> bool {
-    #[cfg(test)]
-    {
-        test_utils::is_windows()
-    }
-    #[cfg(not(test))]
-    {
-        IS_WINDOWS
+/**
+ * The conventions used to convert between file system paths and file URIs: drive letters,
+ * UNC paths and '\' as a separator on Windows.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathStyle {
+    Posix,
+    Windows,
+}
+
+impl PathStyle {
+    /** The path style of the platform the crate is compiled for. */
+    pub const NATIVE: PathStyle = if IS_WINDOWS {
+        PathStyle::Windows
+    } else {
+        PathStyle::Posix
+    };
+}
+
+thread_local! {
+    // `None` unless overridden with `with_path_style`
+    static PATH_STYLE: Cell<Option<PathStyle>> = const { Cell::new(None) };
+}
+
+/**
+ * Returns the path style of the current thread: the one set with `with_path_style`, or else
+ * `PathStyle::NATIVE`.
+ */
+pub fn path_style() -> PathStyle {
+    PATH_STYLE
+        .with(|style| style.get())
+        .unwrap_or(PathStyle::NATIVE)
+}
+
+/**
+ * Runs `f` with `style` as the path style of the current thread, e.g. to test the conversion of
+ * Windows paths on any platform. Other threads are not affected, so tests can use both styles
+ * concurrently. The previous style is restored when `f` returns or panics.
+ *
+ * ```
+ * use vscode_uri_rs::platform::{with_path_style, PathStyle};
+ * use vscode_uri_rs::URI;
+ *
+ * let uri = with_path_style(PathStyle::Windows, || URI::file("C:\\Users\\me")).unwrap();
+ * assert_eq!(uri.to_string(false), "file:///c%3A/Users/me");
+ * ```
+ */
+pub fn with_path_style<R>(style: PathStyle, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<PathStyle>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            PATH_STYLE.with(|style| style.set(self.0));
+        }
     }
+
+    let _restore = Restore(PATH_STYLE.with(|current| current.replace(Some(style))));
+    f()
 }
 
-#[cfg(any(test, feature = "test-utils"))]
-pub mod test_utils {
-    use std::sync::atomic::{AtomicBool, Ordering};
+/**
+ * Returns whether file system paths are Windows paths on the current thread (see `path_style`).
+ */
+pub fn is_windows() -> bool {
+    path_style() == PathStyle::Windows
+}
 
-    static IS_WINDOWS: AtomicBool = AtomicBool::new(false);
+/**
+ * The test helpers of earlier versions, kept for compatibility. The `test-utils` feature that
+ * enabled them is no longer needed.
+ */
+pub mod test_utils {
+    use super::{PathStyle, PATH_STYLE};
 
+    /**
+     * Returns whether file system paths are Windows paths on the current thread.
+     */
+    #[deprecated(note = "use `platform::is_windows`")]
     pub fn is_windows() -> bool {
-        IS_WINDOWS.load(Ordering::SeqCst)
+        super::is_windows()
     }
 
+    /**
+     * Sets the path style of the current thread until it is set again. Unlike before, other
+     * threads are not affected.
+     */
+    #[deprecated(note = "use `platform::with_path_style`, which restores the previous style")]
     pub fn set_is_windows(value: bool) {
-        IS_WINDOWS.store(value, Ordering::SeqCst);
+        let style = if value {
+            PathStyle::Windows
+        } else {
+            PathStyle::Posix
+        };
+        PATH_STYLE.with(|current| current.set(Some(style)));
     }
 }
@@ -0,0 +1,194 @@
+/*
+ * Rust implementation of vscode-uri
+ * https://github.com/microsoft/vscode-uri
+ */
+
+use crate::encoding::{Component, EncodingSet};
+use crate::uri::{format_authority, percent_decode, UriError, UriParts, URI};
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::ops::Range;
+
+/**
+ * The result of `URI::parse_reference`: an absolute URI or a relative reference.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum UriReference {
+    Absolute(URI),
+    Relative(RelativeRef),
+}
+
+impl UriReference {
+    /**
+     * Resolves the reference against `base`. An absolute URI is returned as is.
+     */
+    pub fn resolve(&self, base: &URI) -> Result<URI, UriError> {
+        match self {
+            UriReference::Absolute(uri) => Ok(uri.clone()),
+            UriReference::Relative(reference) => reference.resolve(base),
+        }
+    }
+
+    pub fn is_relative(&self) -> bool {
+        matches!(self, UriReference::Relative(_))
+    }
+}
+
+impl fmt::Display for UriReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UriReference::Absolute(uri) => uri.fmt(f),
+            UriReference::Relative(reference) => reference.fmt(f),
+        }
+    }
+}
+
+/**
+ * A relative reference (RFC 3986, section 4.2) such as `../a/b?x#y`, `/a/b` or `//host/a`,
+ * that is kept as is until it is resolved against a base URI.
+ *
+ * Like in `URI`, the components are stored decoded. Unlike in `URI`, an absent query or
+ * fragment is distinguished from an empty one, since it makes a difference in resolution.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RelativeRef {
+    authority: Option<String>,
+    path: String,
+    query: Option<String>,
+    fragment: Option<String>,
+}
+
+impl RelativeRef {
+    // `parts` must not have a scheme
+    pub(crate) fn from_parts(value: &str, parts: UriParts) -> Self {
+        let decode = |range: Range<usize>| percent_decode(&value[range]);
+        RelativeRef {
+            authority: parts.authority.map(decode),
+            path: decode(parts.path),
+            query: parts.query.map(decode),
+            fragment: parts.fragment.map(decode),
+        }
+    }
+
+    pub fn authority(&self) -> Option<&str> {
+        self.authority.as_deref()
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    pub fn fragment(&self) -> Option<&str> {
+        self.fragment.as_deref()
+    }
+
+    /**
+     * Resolves this reference against `base` as described in RFC 3986, section 5.2.2,
+     * e.g. `../c?x` against `https://host/a/b/index.html` is `https://host/a/c?x`.
+     */
+    pub fn resolve(&self, base: &URI) -> Result<URI, UriError> {
+        let (authority, path, query) = if let Some(authority) = &self.authority {
+            (
+                authority.clone(),
+                remove_dot_segments(&self.path),
+                self.query.clone(),
+            )
+        } else if self.path.is_empty() {
+            (
+                base.authority().to_string(),
+                base.path().to_string(),
+                self.query
+                    .clone()
+                    .or_else(|| Some(base.query().to_string())),
+            )
+        } else if self.path.starts_with('/') {
+            (
+                base.authority().to_string(),
+                remove_dot_segments(&self.path),
+                self.query.clone(),
+            )
+        } else {
+            (
+                base.authority().to_string(),
+                remove_dot_segments(&merge(base, &self.path)),
+                self.query.clone(),
+            )
+        };
+
+        URI::new(
+            base.scheme(),
+            authority,
+            path,
+            query.unwrap_or_default(),
+            self.fragment.clone().unwrap_or_default(),
+        )
+    }
+}
+
+impl fmt::Display for RelativeRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let encoder = |s: &str, component: Component| EncodingSet::VsCode.encode(s, component);
+        if let Some(authority) = &self.authority {
+            write!(f, "//{}", format_authority(authority, &encoder))?;
+        }
+        // ':' is encoded, so a colon in the first segment is not mistaken for a scheme
+        write!(f, "{}", encoder(&self.path, Component::Path))?;
+        if let Some(query) = &self.query {
+            write!(f, "?{}", encoder(query, Component::Query))?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{}", encoder(fragment, Component::Fragment))?;
+        }
+        Ok(())
+    }
+}
+
+// RFC 3986, section 5.2.3
+fn merge(base: &URI, path: &str) -> String {
+    if !base.authority().is_empty() && base.path().is_empty() {
+        return format!("/{}", path);
+    }
+    match base.path().rfind('/') {
+        Some(idx) => format!("{}{}", &base.path()[..=idx], path),
+        None => path.to_string(),
+    }
+}
+
+// RFC 3986, section 5.2.4
+fn remove_dot_segments(path: &str) -> String {
+    let pop_segment = |output: &mut String| {
+        let idx = output.rfind('/').unwrap_or(0);
+        output.truncate(idx);
+    };
+
+    let mut input = path;
+    let mut output = String::with_capacity(path.len());
+    while !input.is_empty() {
+        if let Some(rest) = input
+            .strip_prefix("../")
+            .or_else(|| input.strip_prefix("./"))
+        {
+            input = rest;
+        } else if input.starts_with("/./") || input == "/." {
+            input = if input == "/." { "/" } else { &input[2..] };
+        } else if input.starts_with("/../") || input == "/.." {
+            input = if input == "/.." { "/" } else { &input[3..] };
+            pop_segment(&mut output);
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            let start = usize::from(input.starts_with('/'));
+            let end = input[start..]
+                .find('/')
+                .map_or(input.len(), |idx| start + idx);
+            output.push_str(&input[..end]);
+            input = &input[end..];
+        }
+    }
+    output
+}
@@ -0,0 +1,384 @@
+/*
+ * Rust implementation of vscode-uri
+ * https://github.com/microsoft/vscode-uri
+ */
+
+use crate::schemas::Schemas;
+use crate::uri::{URIChange, UriError, URI};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+pub const WSL_AUTHORITY_KIND: &str = "wsl";
+pub const SSH_AUTHORITY_KIND: &str = "ssh-remote";
+
+/** The host names under which Windows exposes WSL distributions as UNC shares. */
+pub const WSL_UNC_HOST: &str = "wsl$";
+pub const WSL_LOCALHOST_UNC_HOST: &str = "wsl.localhost";
+
+/**
+ * A VS Code remote authority such as `wsl+Ubuntu` or `ssh-remote+my-host`.
+ * The part before the first '+' identifies the kind of remote, the rest is resolver specific.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RemoteAuthority {
+    kind: String,
+    value: String,
+}
+
+impl RemoteAuthority {
+    pub fn new(kind: impl Into<String>, value: impl Into<String>) -> Self {
+        RemoteAuthority {
+            kind: kind.into(),
+            value: value.into(),
+        }
+    }
+
+    pub fn wsl(distro: impl Into<String>) -> Self {
+        Self::new(WSL_AUTHORITY_KIND, distro)
+    }
+
+    pub fn ssh(host: impl Into<String>) -> Self {
+        Self::new(SSH_AUTHORITY_KIND, host)
+    }
+
+    /**
+     * Parses the authority of a `vscode-remote` URI.
+     * Returns `None` if the authority has no `+` separator or an empty kind.
+     */
+    pub fn parse(authority: &str) -> Option<Self> {
+        match authority.split_once('+') {
+            Some((kind, value)) if !kind.is_empty() => Some(Self::new(kind, value)),
+            _ => None,
+        }
+    }
+
+    /**
+     * Returns the remote authority of a `vscode-remote` URI.
+     */
+    pub fn of(uri: &URI) -> Option<Self> {
+        if uri.scheme() != Schemas::VSCODE_REMOTE {
+            return None;
+        }
+        Self::parse(uri.authority())
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    // Authorities are compared case-insensitively since `URI::to_string` lowercases them.
+    fn matches(&self, other: &RemoteAuthority) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.value.eq_ignore_ascii_case(&other.value)
+    }
+}
+
+impl fmt::Display for RemoteAuthority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}+{}", self.kind, self.value)
+    }
+}
+
+/**
+ * Translates between the three views of a remote resource:
+ *  - the local `file:` URI, as seen by the machine running the UI (e.g. `file://wsl$/Ubuntu/home/me`),
+ *  - the `vscode-remote:` URI (e.g. `vscode-remote://wsl+Ubuntu/home/me`),
+ *  - the remote `file:` URI, as seen by the remote machine (e.g. `file:///home/me`).
+ *
+ * Implementors only provide the authority and the path mapping between the local and the remote
+ * machine; the URI conversions are derived from those.
+ */
+pub trait RemoteMapping {
+    /**
+     * The remote authority handled by this mapping.
+     */
+    fn authority(&self) -> RemoteAuthority;
+
+    /**
+     * Maps a local `file:` URI to the path of the same resource on the remote machine.
+     * Returns `None` if the resource is not reachable from the remote machine.
+     */
+    fn local_to_remote_path(&self, local: &URI) -> Option<String>;
+
+    /**
+     * Maps a path on the remote machine to a local `file:` URI.
+     * Returns `Ok(None)` if the resource is not reachable from the local machine.
+     */
+    fn remote_path_to_local(&self, path: &str) -> Result<Option<URI>, UriError>;
+
+    /**
+     * Converts a local `file:` URI to a `vscode-remote:` URI.
+     * Query and fragment are taken from the input URI.
+     */
+    fn to_remote(&self, local: &URI) -> Result<Option<URI>, UriError> {
+        if local.scheme() != Schemas::FILE {
+            return Ok(None);
+        }
+        match self.local_to_remote_path(local) {
+            Some(path) => local
+                .with(URIChange {
+                    scheme: Some(Schemas::VSCODE_REMOTE.to_string()),
+                    authority: Some(self.authority().to_string()),
+                    path: Some(path),
+                    ..Default::default()
+                })
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /**
+     * Converts a `vscode-remote:` URI of this mapping's authority to a local `file:` URI.
+     * Query and fragment are taken from the input URI.
+     */
+    fn to_local(&self, remote: &URI) -> Result<Option<URI>, UriError> {
+        if !self.handles(remote) {
+            return Ok(None);
+        }
+        match self.remote_path_to_local(remote.path())? {
+            Some(local) => local
+                .with(URIChange {
+                    query: Some(remote.query().to_string()),
+                    fragment: Some(remote.fragment().to_string()),
+                    ..Default::default()
+                })
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /**
+     * Converts a `vscode-remote:` URI of this mapping's authority to the `file:` URI
+     * the remote machine uses for the same resource.
+     */
+    fn to_remote_file(&self, remote: &URI) -> Result<Option<URI>, UriError> {
+        if !self.handles(remote) {
+            return Ok(None);
+        }
+        remote
+            .with(URIChange {
+                scheme: Some(Schemas::FILE.to_string()),
+                authority: Some(String::new()),
+                ..Default::default()
+            })
+            .map(Some)
+    }
+
+    /**
+     * Converts a `file:` URI as seen by the remote machine to a `vscode-remote:` URI.
+     */
+    fn remote_file_to_remote(&self, remote_file: &URI) -> Result<Option<URI>, UriError> {
+        if remote_file.scheme() != Schemas::FILE {
+            return Ok(None);
+        }
+        remote_file
+            .with(URIChange {
+                scheme: Some(Schemas::VSCODE_REMOTE.to_string()),
+                authority: Some(self.authority().to_string()),
+                ..Default::default()
+            })
+            .map(Some)
+    }
+
+    /**
+     * Returns whether `uri` is a `vscode-remote:` URI of this mapping's authority.
+     */
+    fn handles(&self, uri: &URI) -> bool {
+        RemoteAuthority::of(uri).is_some_and(|authority| authority.matches(&self.authority()))
+    }
+}
+
+/**
+ * Maps between a Windows host and a WSL distribution:
+ *  - `file://wsl$/<distro>/home/me` (`\\wsl$\<distro>\home\me`) ⇄ `vscode-remote://wsl+<distro>/home/me`
+ *  - `file:///c:/Users/me` ⇄ `vscode-remote://wsl+<distro>/mnt/c/Users/me`
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WslMapping {
+    distro: String,
+    unc_host: String,
+}
+
+impl WslMapping {
+    /**
+     * Creates a mapping for `distro` that produces `\\wsl$\<distro>` local paths.
+     */
+    pub fn new(distro: impl Into<String>) -> Self {
+        Self::with_unc_host(distro, WSL_UNC_HOST)
+    }
+
+    /**
+     * Creates a mapping for `distro` that produces `\\<unc_host>\<distro>` local paths,
+     * e.g. with `WSL_LOCALHOST_UNC_HOST`. Both UNC hosts are accepted as input either way.
+     */
+    pub fn with_unc_host(distro: impl Into<String>, unc_host: impl Into<String>) -> Self {
+        WslMapping {
+            distro: distro.into(),
+            unc_host: unc_host.into(),
+        }
+    }
+
+    pub fn distro(&self) -> &str {
+        &self.distro
+    }
+}
+
+impl RemoteMapping for WslMapping {
+    fn authority(&self) -> RemoteAuthority {
+        RemoteAuthority::wsl(self.distro.clone())
+    }
+
+    fn local_to_remote_path(&self, local: &URI) -> Option<String> {
+        let authority = local.authority();
+        if authority.eq_ignore_ascii_case(WSL_UNC_HOST)
+            || authority.eq_ignore_ascii_case(WSL_LOCALHOST_UNC_HOST)
+        {
+            let path = local.path().strip_prefix('/')?;
+            let (distro, rest) = path.split_once('/').unwrap_or((path, ""));
+            if !distro.eq_ignore_ascii_case(&self.distro) {
+                return None;
+            }
+            return Some(format!("/{}", rest));
+        }
+
+        if authority.is_empty() {
+            let bytes = local.path().as_bytes();
+            if bytes.len() >= 3
+                && bytes[0] == b'/'
+                && bytes[1].is_ascii_alphabetic()
+                && bytes[2] == b':'
+                && (bytes.len() == 3 || bytes[3] == b'/')
+            {
+                let drive = (bytes[1] as char).to_ascii_lowercase();
+                return Some(format!("/mnt/{}{}", drive, &local.path()[3..]));
+            }
+        }
+        None
+    }
+
+    fn remote_path_to_local(&self, path: &str) -> Result<Option<URI>, UriError> {
+        if let Some(rest) = path.strip_prefix("/mnt/") {
+            let bytes = rest.as_bytes();
+            if !bytes.is_empty()
+                && bytes[0].is_ascii_alphabetic()
+                && (bytes.len() == 1 || bytes[1] == b'/')
+            {
+                let drive = (bytes[0] as char).to_ascii_lowercase();
+                let rest = if bytes.len() == 1 { "/" } else { &rest[1..] };
+                return URI::new(Schemas::FILE, "", format!("/{}:{}", drive, rest), "", "")
+                    .map(Some);
+            }
+        }
+
+        let path = if path == "/" { "" } else { path };
+        URI::new(
+            Schemas::FILE,
+            self.unc_host.clone(),
+            format!("/{}{}", self.distro, path),
+            "",
+            "",
+        )
+        .map(Some)
+    }
+}
+
+/**
+ * Maps `vscode-remote://ssh-remote+<host>/...` URIs. The remote file system is not reachable
+ * from the local machine, so only the conversions between the `vscode-remote:` URI and the
+ * remote `file:` URI are available.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshMapping {
+    host: String,
+}
+
+impl SshMapping {
+    pub fn new(host: impl Into<String>) -> Self {
+        SshMapping { host: host.into() }
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+}
+
+impl RemoteMapping for SshMapping {
+    fn authority(&self) -> RemoteAuthority {
+        RemoteAuthority::ssh(self.host.clone())
+    }
+
+    fn local_to_remote_path(&self, _local: &URI) -> Option<String> {
+        None
+    }
+
+    fn remote_path_to_local(&self, _path: &str) -> Result<Option<URI>, UriError> {
+        Ok(None)
+    }
+}
+
+/**
+ * A set of mappings that dispatches on the authority of `vscode-remote:` URIs.
+ */
+#[derive(Default)]
+pub struct RemoteMappings {
+    mappings: Vec<Box<dyn RemoteMapping + Send + Sync>>,
+}
+
+impl RemoteMappings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, mapping: impl RemoteMapping + Send + Sync + 'static) {
+        self.mappings.push(Box::new(mapping));
+    }
+
+    /**
+     * Returns the mapping registered for the authority of `remote`, if any.
+     */
+    pub fn find(&self, remote: &URI) -> Option<&(dyn RemoteMapping + Send + Sync)> {
+        self.mappings
+            .iter()
+            .find(|mapping| mapping.handles(remote))
+            .map(|mapping| mapping.as_ref())
+    }
+
+    /**
+     * Converts a local `file:` URI using the first mapping that can reach it.
+     */
+    pub fn to_remote(&self, local: &URI) -> Result<Option<URI>, UriError> {
+        for mapping in &self.mappings {
+            if let Some(remote) = mapping.to_remote(local)? {
+                return Ok(Some(remote));
+            }
+        }
+        Ok(None)
+    }
+
+    /**
+     * Converts a `vscode-remote:` URI using the mapping registered for its authority.
+     */
+    pub fn to_local(&self, remote: &URI) -> Result<Option<URI>, UriError> {
+        match self.find(remote) {
+            Some(mapping) => mapping.to_local(remote),
+            None => Ok(None),
+        }
+    }
+
+    /**
+     * Converts a `vscode-remote:` URI to the `file:` URI of the remote machine using the
+     * mapping registered for its authority.
+     */
+    pub fn to_remote_file(&self, remote: &URI) -> Result<Option<URI>, UriError> {
+        match self.find(remote) {
+            Some(mapping) => mapping.to_remote_file(remote),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+/*
+ * Rust implementation of vscode-uri
+ * https://github.com/microsoft/vscode-uri
+ */
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::string::ToString;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(not(feature = "std"))]
+use spin::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+#[cfg(feature = "std")]
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/**
+ * Well-known URI schemes used by VS Code and its built-in extensions.
+ */
+pub struct Schemas;
+
+impl Schemas {
+    /** A schema that is used for models that exist in memory only. */
+    pub const IN_MEMORY: &'static str = "inmemory";
+    /** A schema that is used for setting files. */
+    pub const VSCODE: &'static str = "vscode";
+    /** A schema that is used for internal private files. */
+    pub const INTERNAL: &'static str = "private";
+    pub const WALK_THROUGH: &'static str = "walkThrough";
+    pub const WALK_THROUGH_SNIPPET: &'static str = "walkThroughSnippet";
+
+    pub const HTTP: &'static str = "http";
+    pub const HTTPS: &'static str = "https";
+    pub const FILE: &'static str = "file";
+    pub const MAILTO: &'static str = "mailto";
+    pub const UNTITLED: &'static str = "untitled";
+    pub const DATA: &'static str = "data";
+    pub const COMMAND: &'static str = "command";
+
+    pub const VSCODE_REMOTE: &'static str = "vscode-remote";
+    pub const VSCODE_REMOTE_RESOURCE: &'static str = "vscode-remote-resource";
+    pub const VSCODE_MANAGED_REMOTE_RESOURCE: &'static str = "vscode-managed-remote-resource";
+    pub const VSCODE_USER_DATA: &'static str = "vscode-userdata";
+    pub const VSCODE_CUSTOM_EDITOR: &'static str = "vscode-custom-editor";
+    pub const VSCODE_NOTEBOOK_CELL: &'static str = "vscode-notebook-cell";
+    pub const VSCODE_NOTEBOOK_CELL_METADATA: &'static str = "vscode-notebook-cell-metadata";
+    pub const VSCODE_NOTEBOOK_CELL_OUTPUT: &'static str = "vscode-notebook-cell-output";
+    pub const VSCODE_INTERACTIVE_INPUT: &'static str = "vscode-interactive-input";
+    pub const VSCODE_SETTINGS: &'static str = "vscode-settings";
+    pub const VSCODE_WORKSPACE_TRUST: &'static str = "vscode-workspace-trust";
+    pub const VSCODE_TERMINAL: &'static str = "vscode-terminal";
+    pub const VSCODE_SOURCE_CONTROL: &'static str = "vscode-scm";
+    pub const VSCODE_FILE_RESOURCE: &'static str = "vscode-file";
+    /** Virtual file systems provided by the Remote Repositories extension. */
+    pub const VSCODE_VFS: &'static str = "vscode-vfs";
+
+    pub const WEBVIEW_PANEL: &'static str = "webview-panel";
+    pub const VSCODE_WEBVIEW: &'static str = "vscode-webview";
+    pub const EXTENSION: &'static str = "extension";
+    pub const TMP: &'static str = "tmp";
+    pub const VSLS: &'static str = "vsls";
+    pub const COMMENTS_INPUT: &'static str = "comment";
+    pub const CODE_SETTING: &'static str = "code-setting";
+    pub const OUTPUT_CHANNEL: &'static str = "output";
+    pub const ACCESSIBLE_VIEW: &'static str = "accessible-view";
+    /** Resources at a given revision, provided by the built-in Git extension. */
+    pub const GIT: &'static str = "git";
+}
+
+/**
+ * Per-scheme rules consulted when constructing and formatting URIs.
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SchemeRules {
+    /** The authority must not be empty. */
+    pub requires_authority: bool,
+    /** Relative paths are made absolute by prepending '/'. */
+    pub absolute_path: bool,
+    /** Paths that differ only in case identify the same resource. */
+    pub case_insensitive_path: bool,
+    /** The port implied when the authority does not specify one. */
+    pub default_port: Option<u16>,
+    /** `//` is written even when the authority is empty, as in `file:///path`. */
+    pub authority_slashes: bool,
+}
+
+// The rules of the built-in schemes, which are looked up without a lock. Like in vscode-uri, they
+// only apply to the lowercase names.
+fn builtin_rules(scheme: &str) -> Option<SchemeRules> {
+    if scheme == Schemas::FILE {
+        Some(SchemeRules {
+            absolute_path: true,
+            authority_slashes: true,
+            ..Default::default()
+        })
+    } else if scheme == Schemas::HTTP {
+        Some(SchemeRules {
+            absolute_path: true,
+            default_port: Some(80),
+            ..Default::default()
+        })
+    } else if scheme == Schemas::HTTPS {
+        Some(SchemeRules {
+            absolute_path: true,
+            default_port: Some(443),
+            ..Default::default()
+        })
+    } else {
+        None
+    }
+}
+
+// The rules registered with `SchemeRegistry::register`, by lowercased scheme
+static REGISTRY: RwLock<BTreeMap<String, SchemeRules>> = RwLock::new(BTreeMap::new());
+// Whether `REGISTRY` is not empty, so that lookups only take the lock if something is registered
+static HAS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "std")]
+thread_local! {
+    // the rules set with `SchemeRegistry::with_rules`, innermost last
+    static SCOPED: RefCell<Vec<(String, SchemeRules)>> = const { RefCell::new(Vec::new()) };
+}
+
+#[cfg(feature = "std")]
+fn lock_read() -> RwLockReadGuard<'static, BTreeMap<String, SchemeRules>> {
+    REGISTRY.read().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(feature = "std")]
+fn lock_write() -> RwLockWriteGuard<'static, BTreeMap<String, SchemeRules>> {
+    REGISTRY.write().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(not(feature = "std"))]
+fn lock_read() -> RwLockReadGuard<'static, BTreeMap<String, SchemeRules>> {
+    REGISTRY.read()
+}
+
+#[cfg(not(feature = "std"))]
+fn lock_write() -> RwLockWriteGuard<'static, BTreeMap<String, SchemeRules>> {
+    REGISTRY.write()
+}
+
+fn write_registry<R>(f: impl FnOnce(&mut BTreeMap<String, SchemeRules>) -> R) -> R {
+    let mut registry = lock_write();
+    let result = f(&mut registry);
+    HAS_REGISTERED.store(!registry.is_empty(), Ordering::Release);
+    result
+}
+
+#[cfg(feature = "std")]
+fn scoped_rules(scheme: &str) -> Option<SchemeRules> {
+    SCOPED.with(|scoped| {
+        let scoped = scoped.borrow();
+        let mut matching = scoped
+            .iter()
+            .rev()
+            .filter(|(s, _)| s.eq_ignore_ascii_case(scheme));
+        matching.next().map(|(_, rules)| *rules)
+    })
+}
+
+#[cfg(not(feature = "std"))]
+fn scoped_rules(_scheme: &str) -> Option<SchemeRules> {
+    None
+}
+
+fn registered_rules(scheme: &str) -> Option<SchemeRules> {
+    if !HAS_REGISTERED.load(Ordering::Acquire) {
+        return None;
+    }
+    lock_read().get(&scheme.to_lowercase()).copied()
+}
+
+/**
+ * Registry of scheme rules. `file`, `http` and `https` have built-in rules; unknown schemes get
+ * `SchemeRules::default()`. Like in vscode-uri, the built-in rules only apply to the lowercase
+ * names, so `HTTP:foo` keeps the path `foo`, while registered schemes are matched
+ * case-insensitively.
+ *
+ * Rules are looked up in the scopes of `with_rules` on the current thread first, then in the
+ * process-wide registrations of `register`, then in the built-in rules. Prefer `with_rules`
+ * where the rules only matter to a piece of code, e.g. in tests, as registrations are seen by
+ * every caller in the process.
+ */
+pub struct SchemeRegistry;
+
+impl SchemeRegistry {
+    /**
+     * Registers `rules` for `scheme` for the whole process, replacing any previously registered
+     * rules. The rules of a built-in scheme are overridden until `unregister` is called.
+     */
+    pub fn register(scheme: &str, rules: SchemeRules) {
+        write_registry(|registry| registry.insert(scheme.to_lowercase(), rules));
+    }
+
+    /**
+     * Removes the rules registered for `scheme` and returns them. Built-in rules cannot be removed.
+     */
+    pub fn unregister(scheme: &str) -> Option<SchemeRules> {
+        write_registry(|registry| registry.remove(&scheme.to_lowercase()))
+    }
+
+    /**
+     * Runs `f` with `rules` for `scheme` on the current thread, taking precedence over the
+     * registered and the built-in rules. Other threads are not affected, and the previous rules
+     * are restored when `f` returns or panics.
+     *
+     * ```
+     * use vscode_uri_rs::{SchemeRegistry, SchemeRules, URI};
+     *
+     * let rules = SchemeRules { absolute_path: true, ..Default::default() };
+     * let uri = SchemeRegistry::with_rules("mem", rules, || URI::parse("mem:a")).unwrap();
+     * assert_eq!(uri.path(), "/a");
+     * assert_eq!(URI::parse("mem:a").unwrap().path(), "a");
+     * ```
+     */
+    #[cfg(feature = "std")]
+    pub fn with_rules<R>(scheme: &str, rules: SchemeRules, f: impl FnOnce() -> R) -> R {
+        struct Restore;
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                SCOPED.with(|scoped| scoped.borrow_mut().pop());
+            }
+        }
+
+        SCOPED.with(|scoped| scoped.borrow_mut().push((scheme.to_string(), rules)));
+        let _restore = Restore;
+        f()
+    }
+
+    /**
+     * Whether `scheme` has rules other than the default ones: built-in, registered or scoped.
+     */
+    pub fn is_registered(scheme: &str) -> bool {
+        scoped_rules(scheme)
+            .or_else(|| registered_rules(scheme))
+            .or_else(|| builtin_rules(scheme))
+            .is_some()
+    }
+
+    /**
+     * Returns the rules for `scheme`, or the default rules if it is not registered.
+     */
+    pub fn rules(scheme: &str) -> SchemeRules {
+        scoped_rules(scheme)
+            .or_else(|| registered_rules(scheme))
+            .or_else(|| builtin_rules(scheme))
+            .unwrap_or_default()
+    }
+}
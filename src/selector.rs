@@ -0,0 +1,154 @@
+/*
+ * Rust implementation of vscode-uri
+ * https://github.com/microsoft/vscode-uri
+ */
+
+use crate::glob::Glob;
+use crate::uri::URI;
+
+/**
+ * A document filter as used by LSP and VS Code's `DocumentSelector`.
+ * All set properties must match; a plain language id selector is a filter with only `language` set.
+ */
+#[derive(Debug, Default, Clone)]
+pub struct DocumentFilter {
+    /** A language id like `typescript`, or `*`. */
+    pub language: Option<String>,
+    /** A URI scheme like `file` or `untitled`, or `*`. */
+    pub scheme: Option<String>,
+    /** A glob pattern matched against the file system path, or a relative pattern. */
+    pub pattern: Option<Glob>,
+    /** The type of a notebook, like `jupyter-notebook`, or `*`. */
+    pub notebook_type: Option<String>,
+    /** Also match documents that are not synchronized with the extension host. */
+    pub has_access_to_all_models: bool,
+}
+
+impl DocumentFilter {
+    pub fn language(language: impl Into<String>) -> Self {
+        DocumentFilter {
+            language: Some(language.into()),
+            ..Default::default()
+        }
+    }
+
+    /**
+     * Scores how well this filter matches `candidate`, following VS Code's `score()`:
+     * 10 for an exact match, 5 for a `*` match and 0 for no match.
+     */
+    pub fn score(&self, candidate: &DocumentCandidate) -> u32 {
+        if !candidate.is_synchronized && !self.has_access_to_all_models {
+            return 0;
+        }
+
+        // a filter that targets a notebook is matched against the notebook uri instead
+        let uri = match (non_empty(&self.notebook_type), candidate.notebook_uri) {
+            (Some(_), Some(notebook_uri)) => notebook_uri,
+            _ => candidate.uri,
+        };
+
+        let mut ret = 0;
+
+        if let Some(scheme) = non_empty(&self.scheme) {
+            if scheme == uri.scheme() {
+                ret = 10;
+            } else if scheme == "*" {
+                ret = 5;
+            } else {
+                return 0;
+            }
+        }
+
+        if let Some(language) = non_empty(&self.language) {
+            if language == candidate.language {
+                ret = 10;
+            } else if language == "*" {
+                ret = ret.max(5);
+            } else {
+                return 0;
+            }
+        }
+
+        if let Some(notebook_type) = non_empty(&self.notebook_type) {
+            if Some(notebook_type) == candidate.notebook_type {
+                ret = 10;
+            } else if notebook_type == "*" && candidate.notebook_type.is_some() {
+                ret = ret.max(5);
+            } else {
+                return 0;
+            }
+        }
+
+        if let Some(pattern) = &self.pattern {
+            let matches = if pattern.base().is_some() {
+                pattern.is_match(uri)
+            } else {
+                let fs_path = uri.fs_path();
+                let fs_path = fs_path.to_string_lossy();
+                pattern.pattern() == fs_path || pattern.is_match_path(&fs_path)
+            };
+            if matches {
+                ret = 10;
+            } else {
+                return 0;
+            }
+        }
+
+        ret
+    }
+}
+
+// Like in VS Code, empty strings are treated as unset.
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().filter(|value| !value.is_empty())
+}
+
+/**
+ * A document to score a selector against.
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct DocumentCandidate<'a> {
+    pub uri: &'a URI,
+    pub language: &'a str,
+    /** Whether the document is synchronized with the extension host. */
+    pub is_synchronized: bool,
+    /** The notebook containing the document, if it is a notebook cell. */
+    pub notebook_uri: Option<&'a URI>,
+    pub notebook_type: Option<&'a str>,
+}
+
+impl<'a> DocumentCandidate<'a> {
+    /**
+     * A synchronized document that is not part of a notebook.
+     */
+    pub fn new(uri: &'a URI, language: &'a str) -> Self {
+        DocumentCandidate {
+            uri,
+            language,
+            is_synchronized: true,
+            notebook_uri: None,
+            notebook_type: None,
+        }
+    }
+}
+
+/**
+ * Scores a document selector, i.e. a list of filters, against `candidate`.
+ * The result is the highest score of any filter.
+ */
+pub fn score(selector: &[DocumentFilter], candidate: &DocumentCandidate) -> u32 {
+    let mut ret = 0;
+    for filter in selector {
+        let value = filter.score(candidate);
+        if value == 10 {
+            // already at the highest
+            return value;
+        }
+        ret = ret.max(value);
+    }
+    ret
+}
+
+pub fn matches(selector: &[DocumentFilter], candidate: &DocumentCandidate) -> bool {
+    score(selector, candidate) > 0
+}
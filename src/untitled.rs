@@ -0,0 +1,93 @@
+/*
+ * Rust implementation of vscode-uri
+ * https://github.com/microsoft/vscode-uri
+ */
+
+use crate::schemas::Schemas;
+use crate::uri::{URIChange, UriError, URI};
+use alloc::format;
+use alloc::string::{String, ToString};
+
+const UNTITLED_PREFIX: &str = "Untitled-";
+
+/**
+ * Helpers for `untitled:` URIs, which identify editors that have not been saved yet.
+ *
+ * VS Code names new untitled editors `untitled:Untitled-<n>`. An untitled editor that already
+ * knows where it will be saved uses the path of that file instead, e.g. `untitled:/Users/me/new.ts`.
+ */
+pub struct UntitledUri;
+
+impl UntitledUri {
+    pub const SCHEME: &'static str = Schemas::UNTITLED;
+
+    /**
+     * Returns the URI VS Code uses for the `index`th new untitled editor, e.g. `untitled:Untitled-1`.
+     */
+    pub fn generate(index: u32) -> Result<URI, UriError> {
+        URI::new(
+            Self::SCHEME,
+            "",
+            format!("{}{}", UNTITLED_PREFIX, index),
+            "",
+            "",
+        )
+    }
+
+    /**
+     * Returns the untitled URI for an editor that will be saved to `resource`.
+     */
+    pub fn for_associated_resource(resource: &URI) -> Result<URI, UriError> {
+        resource.with(URIChange {
+            scheme: Some(Self::SCHEME.to_string()),
+            ..Default::default()
+        })
+    }
+
+    /**
+     * Returns the number of an `untitled:Untitled-<n>` URI.
+     */
+    pub fn index(uri: &URI) -> Option<u32> {
+        if uri.scheme() != Self::SCHEME {
+            return None;
+        }
+        let index = uri.path().strip_prefix(UNTITLED_PREFIX)?;
+        if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        index.parse().ok()
+    }
+
+    /**
+     * Returns whether the path of an untitled URI is the path of the file it will be saved to.
+     */
+    pub fn has_associated_path(uri: &URI) -> bool {
+        uri.scheme() == Self::SCHEME && uri.path().len() > 1 && uri.path().starts_with('/')
+    }
+
+    /**
+     * Returns the `file:` URI an untitled editor will be saved to, if it has an associated path.
+     */
+    pub fn associated_file(uri: &URI) -> Option<URI> {
+        if !Self::has_associated_path(uri) {
+            return None;
+        }
+        uri.with(URIChange {
+            scheme: Some(Schemas::FILE.to_string()),
+            ..Default::default()
+        })
+        .ok()
+    }
+
+    /**
+     * Returns the name VS Code shows for an untitled editor: the base name of the associated
+     * path, or the path itself (e.g. `Untitled-1`).
+     */
+    pub fn name(uri: &URI) -> String {
+        if Self::has_associated_path(uri) {
+            uri.segments().last().unwrap_or_default().to_string()
+        } else {
+            uri.path().to_string()
+        }
+    }
+}
@@ -4,58 +4,152 @@
  */
 
 use crate::char_code::CharCode;
+use crate::encoding::{decode_percent, Component, EncodeOptions, EncodingSet};
+#[cfg(feature = "std")]
 use crate::platform::is_windows;
-use lazy_static::lazy_static;
-use percent_encoding::{percent_decode_str, percent_encode, CONTROLS};
-use regex::Regex;
-use std::collections::HashMap;
-use std::fmt;
+use crate::reference::{RelativeRef, UriReference};
+use crate::schemas::{Schemas, SchemeRegistry, SchemeRules};
+use crate::utils::Utils;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+use percent_encoding::{percent_decode_str, percent_encode, AsciiSet, NON_ALPHANUMERIC};
+#[cfg(all(feature = "std", unix))]
+use std::ffi::OsString;
+#[cfg(feature = "std")]
 use std::path::{Path, PathBuf};
 
-lazy_static! {
-    static ref SCHEME_PATTERN: Regex = Regex::new(r"^[a-zA-Z][a-zA-Z0-9+.-]*$").unwrap();
-    static ref SINGLE_SLASH_START: Regex = Regex::new(r"^/").unwrap();
-    static ref DOUBLE_SLASH_START: Regex = Regex::new(r"^//").unwrap();
-    static ref URI_REGEX: Regex =
-        Regex::new(r"^(([^:/?#]+?):)?(//([^/?#]*))?([^?#]*)(\?([^#]*))?(#(.*))?").unwrap();
-    static ref ENCODED_AS_HEX: Regex = Regex::new(r"(%[0-9A-Za-z][0-9A-Za-z])+").unwrap();
+// Without `std` there is no platform to ask; paths are formatted as POSIX paths.
+#[cfg(not(feature = "std"))]
+fn is_windows() -> bool {
+    false
 }
 
 const EMPTY: &str = "";
 const SLASH: &str = "/";
-lazy_static! {
-    static ref PATH_SEP_MARKER: Option<u8> = if is_windows() { Some(1) } else { None };
-}
-
-lazy_static! {
-    static ref ENCODE_TABLE: HashMap<u32, &'static str> = {
-        let mut m = HashMap::new();
-        m.insert(CharCode::Colon as u32, "%3A");
-        m.insert(CharCode::Slash as u32, "%2F");
-        m.insert(CharCode::QuestionMark as u32, "%3F");
-        m.insert(CharCode::Hash as u32, "%23");
-        m.insert(CharCode::OpenSquareBracket as u32, "%5B");
-        m.insert(CharCode::CloseSquareBracket as u32, "%5D");
-        m.insert(CharCode::AtSign as u32, "%40");
-
-        m.insert(CharCode::ExclamationMark as u32, "%21");
-        m.insert(CharCode::DollarSign as u32, "%24");
-        m.insert(CharCode::Ampersand as u32, "%26");
-        m.insert(CharCode::SingleQuote as u32, "%27");
-        m.insert(CharCode::OpenParen as u32, "%28");
-        m.insert(CharCode::CloseParen as u32, "%29");
-        m.insert(CharCode::Asterisk as u32, "%2A");
-        m.insert(CharCode::Plus as u32, "%2B");
-        m.insert(CharCode::Comma as u32, "%2C");
-        m.insert(CharCode::Semicolon as u32, "%3B");
-        m.insert(CharCode::Equals as u32, "%3D");
-        m.insert(CharCode::PercentSign as u32, "%25");
-
-        m.insert(CharCode::Space as u32, "%20");
-        m
-    };
+// Paths of this length or longer need the `\\?\` prefix to be accepted by the Win32 API
+#[cfg(feature = "std")]
+const WIN32_MAX_PATH: usize = 260;
+
+const ENCODE_TABLE: [(u32, &str); 20] = [
+    (CharCode::Colon as u32, "%3A"),
+    (CharCode::Slash as u32, "%2F"),
+    (CharCode::QuestionMark as u32, "%3F"),
+    (CharCode::Hash as u32, "%23"),
+    (CharCode::OpenSquareBracket as u32, "%5B"),
+    (CharCode::CloseSquareBracket as u32, "%5D"),
+    (CharCode::AtSign as u32, "%40"),
+    (CharCode::ExclamationMark as u32, "%21"),
+    (CharCode::DollarSign as u32, "%24"),
+    (CharCode::Ampersand as u32, "%26"),
+    (CharCode::SingleQuote as u32, "%27"),
+    (CharCode::OpenParen as u32, "%28"),
+    (CharCode::CloseParen as u32, "%29"),
+    (CharCode::Asterisk as u32, "%2A"),
+    (CharCode::Plus as u32, "%2B"),
+    (CharCode::Comma as u32, "%2C"),
+    (CharCode::Semicolon as u32, "%3B"),
+    (CharCode::Equals as u32, "%3D"),
+    (CharCode::PercentSign as u32, "%25"),
+    (CharCode::Space as u32, "%20"),
+];
+
+// The characters JavaScript's `encodeURIComponent` escapes: all but `A-Z a-z 0-9 - _ . ! ~ * ' ( )`
+const URI_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'!')
+    .remove(b'~')
+    .remove(b'*')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')');
+
+fn encode_table(code: u32) -> Option<&'static str> {
+    ENCODE_TABLE
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, escaped)| *escaped)
+}
+
+// `^[a-zA-Z][a-zA-Z0-9+.-]*$`
+fn is_valid_scheme(scheme: &str) -> bool {
+    let mut bytes = scheme.bytes();
+    bytes.next().is_some_and(|b| b.is_ascii_alphabetic())
+        && bytes.all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'.' | b'-'))
+}
+
+// The components of a URI as split by the regular expression of RFC 3986, appendix B:
+// `^(([^:/?#]+):)?(//([^/?#]*))?([^?#]*)(\?([^#]*))?(#(.*))?`
+// The ranges exclude the delimiters. Like `.` in the expression, the fragment ends at a line feed.
+pub(crate) struct UriParts {
+    pub(crate) scheme: Option<Range<usize>>,
+    pub(crate) authority: Option<Range<usize>>,
+    pub(crate) path: Range<usize>,
+    pub(crate) query: Option<Range<usize>>,
+    pub(crate) fragment: Option<Range<usize>>,
+}
+
+impl UriParts {
+    pub(crate) fn split(value: &str) -> Self {
+        let end_of = |start: usize, delimiters: &[char]| {
+            value[start..]
+                .find(delimiters)
+                .map_or(value.len(), |idx| start + idx)
+        };
+
+        let mut pos = 0;
+        let scheme = match value.find([':', '/', '?', '#']) {
+            Some(idx) if idx > 0 && value.as_bytes()[idx] == b':' => {
+                pos = idx + 1;
+                Some(0..idx)
+            }
+            _ => None,
+        };
+        let authority = if value[pos..].starts_with("//") {
+            let end = end_of(pos + 2, &['/', '?', '#']);
+            let authority = pos + 2..end;
+            pos = end;
+            Some(authority)
+        } else {
+            None
+        };
+        let path = pos..end_of(pos, &['?', '#']);
+        pos = path.end;
+        let query = if value[pos..].starts_with('?') {
+            let end = end_of(pos + 1, &['#']);
+            let query = pos + 1..end;
+            pos = end;
+            Some(query)
+        } else {
+            None
+        };
+        let fragment = if value[pos..].starts_with('#') {
+            Some(pos + 1..end_of(pos + 1, &['\n']))
+        } else {
+            None
+        };
+
+        UriParts {
+            scheme,
+            authority,
+            path,
+            query,
+            fragment,
+        }
+    }
 }
 
+/**
+ * An error when creating a URI. Each error carries an `ErrorContext`: the text in which it was
+ * found (`input`), the byte range of the problem in that text (`span`) and the component it is
+ * about (`component`). For `parse` and its variants, the text is the parsed value, otherwise the
+ * value of the offending component, segment or path.
+ */
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum UriError {
     MissingScheme {
@@ -64,29 +158,314 @@ pub enum UriError {
         path: String,
         query: String,
         fragment: String,
+        context: Box<ErrorContext>,
+    },
+    IllegalSchemeCharacters {
+        context: Box<ErrorContext>,
+    },
+    InvalidAuthorityPath {
+        context: Box<ErrorContext>,
+    },
+    InvalidPathWithoutAuthority {
+        context: Box<ErrorContext>,
+    },
+    InvalidPathSegment {
+        context: Box<ErrorContext>,
+    },
+    UnsupportedDevicePath {
+        context: Box<ErrorContext>,
+    },
+    RelativeFilePath {
+        context: Box<ErrorContext>,
+    },
+    WorkingDirectoryUnavailable {
+        reason: String,
+        context: Box<ErrorContext>,
+    },
+    MissingAuthority {
+        scheme: String,
+        context: Box<ErrorContext>,
+    },
+    /** The span covers the whole pattern, which is the input. */
+    InvalidGlobPattern {
+        context: Box<ErrorContext>,
+    },
+    InvalidPercentEncoding {
+        context: Box<ErrorContext>,
     },
-    IllegalSchemeCharacters,
-    InvalidAuthorityPath,
-    InvalidPathWithoutAuthority,
 }
 
-impl std::fmt::Display for UriError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+/**
+ * Where a `UriError` was found, see `UriError::input`, `UriError::span` and `UriError::component`.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorContext {
+    pub input: String,
+    pub span: Range<usize>,
+    pub component: UriComponent,
+}
+
+/**
+ * A component of a URI, as reported by errors.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UriComponent {
+    Scheme,
+    Authority,
+    Path,
+    Query,
+    Fragment,
+}
+
+impl fmt::Display for UriComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            UriError::MissingScheme { scheme, authority, path, query, fragment } => {
+            UriComponent::Scheme => write!(f, "scheme"),
+            UriComponent::Authority => write!(f, "authority"),
+            UriComponent::Path => write!(f, "path"),
+            UriComponent::Query => write!(f, "query"),
+            UriComponent::Fragment => write!(f, "fragment"),
+        }
+    }
+}
+
+impl fmt::Display for UriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let input = self.input();
+        match self {
+            UriError::MissingScheme { scheme, authority, path, query, fragment, .. } => {
                 write!(f, "Scheme is missing: {{scheme: \"{}\", authority: \"{}\", path: \"{}\", query: \"{}\", fragment: \"{}\"}}", 
                     scheme, authority, path, query, fragment)
             }
-            UriError::IllegalSchemeCharacters => write!(f, "Scheme contains illegal characters"),
-            UriError::InvalidAuthorityPath => write!(f, "If a URI contains an authority component, then the path component must either be empty or begin with a slash (\"/\") character"),
-            UriError::InvalidPathWithoutAuthority => write!(f, "If a URI does not contain an authority component, then the path cannot begin with two slash characters (\"//\")"),
+            UriError::IllegalSchemeCharacters { .. } => write!(f, "Scheme contains illegal characters"),
+            UriError::InvalidAuthorityPath { .. } => write!(f, "If a URI contains an authority component, then the path component must either be empty or begin with a slash (\"/\") character"),
+            UriError::InvalidPathWithoutAuthority { .. } => write!(f, "If a URI does not contain an authority component, then the path cannot begin with two slash characters (\"//\")"),
+            UriError::InvalidPathSegment { .. } if input == "." || input == ".." => write!(f, "Path segment \"{}\" is not a file name", input),
+            UriError::InvalidPathSegment { .. } => write!(f, "Path segment \"{}\" must not be empty or contain a slash (\"/\") character", input),
+            UriError::MissingAuthority { scheme, .. } => write!(f, "URIs with the scheme \"{}\" must contain an authority component", scheme),
+            UriError::InvalidGlobPattern { .. } => write!(f, "Invalid glob pattern \"{}\"", input),
+            UriError::UnsupportedDevicePath { .. } => write!(f, "The Windows device path \"{}\" cannot be represented as a file URI", input),
+            UriError::RelativeFilePath { .. } => write!(f, "The relative path \"{}\" cannot be represented as a file URI without a base", input),
+            UriError::WorkingDirectoryUnavailable { reason, .. } => write!(f, "The relative path \"{}\" cannot be resolved against the working directory: {}", input, reason),
+            UriError::InvalidPercentEncoding { .. } => write!(f, "Invalid percent-encoding in the {} at offset {}", self.component(), self.span().start),
         }
     }
 }
 
-impl std::error::Error for UriError {}
+impl UriError {
+    /**
+     * The text in which the error was found.
+     */
+    pub fn input(&self) -> &str {
+        &self.context().input
+    }
+
+    /**
+     * The byte range of the problem in `input`, e.g. the illegal character of a scheme. It is
+     * empty for something that is missing, at the position where it was expected.
+     */
+    pub fn span(&self) -> Range<usize> {
+        self.context().span.clone()
+    }
 
-fn validate_uri(uri: &URI, strict: bool) -> Result<(), UriError> {
+    /**
+     * The component the error is about. For `URI::with`, it is the changed component that
+     * made the URI invalid, e.g. the authority added to a URI with a relative path.
+     */
+    pub fn component(&self) -> UriComponent {
+        self.context().component
+    }
+
+    /**
+     * Where the error was found: `input`, `span` and `component` together.
+     */
+    pub fn context(&self) -> &ErrorContext {
+        match self {
+            UriError::MissingScheme { context, .. }
+            | UriError::IllegalSchemeCharacters { context }
+            | UriError::InvalidAuthorityPath { context }
+            | UriError::InvalidPathWithoutAuthority { context }
+            | UriError::InvalidPathSegment { context }
+            | UriError::UnsupportedDevicePath { context }
+            | UriError::RelativeFilePath { context }
+            | UriError::WorkingDirectoryUnavailable { context, .. }
+            | UriError::MissingAuthority { context, .. }
+            | UriError::InvalidGlobPattern { context }
+            | UriError::InvalidPercentEncoding { context } => context,
+        }
+    }
+
+    fn context_mut(&mut self) -> &mut ErrorContext {
+        match self {
+            UriError::MissingScheme { context, .. }
+            | UriError::IllegalSchemeCharacters { context }
+            | UriError::InvalidAuthorityPath { context }
+            | UriError::InvalidPathWithoutAuthority { context }
+            | UriError::InvalidPathSegment { context }
+            | UriError::UnsupportedDevicePath { context }
+            | UriError::RelativeFilePath { context }
+            | UriError::WorkingDirectoryUnavailable { context, .. }
+            | UriError::MissingAuthority { context, .. }
+            | UriError::InvalidGlobPattern { context }
+            | UriError::InvalidPercentEncoding { context } => context,
+        }
+    }
+
+    pub(crate) fn invalid_path_segment(segment: &str) -> Self {
+        UriError::InvalidPathSegment {
+            context: Box::new(ErrorContext {
+                input: segment.to_string(),
+                span: match segment.find('/') {
+                    Some(idx) => idx..idx + 1,
+                    None if segment == "." || segment == ".." => 0..segment.len(),
+                    None => 0..0,
+                },
+                component: UriComponent::Path,
+            }),
+        }
+    }
+
+    // Moves an error found in the decoded components of `value` (which `parts` splits) to
+    // `value`, e.g. from the path `//a` to the `%2F%2F` of `foo:%2F%2Fa`.
+    pub(crate) fn located_in(mut self, value: &str, parts: &UriParts) -> Self {
+        let ErrorContext {
+            input,
+            span,
+            component,
+        } = self.context_mut();
+        let range = match component {
+            UriComponent::Scheme => parts.scheme.clone(),
+            UriComponent::Authority => parts.authority.clone(),
+            UriComponent::Path => Some(parts.path.clone()),
+            UriComponent::Query => parts.query.clone(),
+            UriComponent::Fragment => parts.fragment.clone(),
+        };
+        *span = match range {
+            Some(range) => {
+                let raw = &value[range.clone()];
+                range.start + raw_offset(raw, span.start)..range.start + raw_offset(raw, span.end)
+            }
+            // where the missing component would start
+            None if *component == UriComponent::Scheme => 0..0,
+            None => parts.path.start..parts.path.start,
+        };
+        *input = value.to_string();
+        self
+    }
+
+    // Attributes an error of `URI::with` to a changed component: its own component if that
+    // changed, or else the first of `changes` that did, whose whole value becomes the input.
+    fn blamed_on(mut self, changes: &[(UriComponent, bool, &String)]) -> Self {
+        let own = self.component();
+        if changes
+            .iter()
+            .any(|&(component, changed, _)| changed && component == own)
+        {
+            return self;
+        }
+        if let Some(&(changed_component, _, value)) = changes.iter().find(|change| change.1) {
+            *self.context_mut() = ErrorContext {
+                input: value.clone(),
+                span: 0..value.len(),
+                component: changed_component,
+            };
+        }
+        self
+    }
+
+    // Moves an error found in `value[range]` to `value`.
+    pub(crate) fn offset_by(mut self, value: &str, range: &Range<usize>) -> Self {
+        let ErrorContext { input, span, .. } = self.context_mut();
+        *span = range.start + span.start..range.start + span.end;
+        *input = value.to_string();
+        self
+    }
+
+    // A short description of the problem at `span`.
+    #[cfg(feature = "miette")]
+    fn label(&self) -> &'static str {
+        match self {
+            UriError::MissingScheme { .. } => "missing scheme",
+            UriError::IllegalSchemeCharacters { .. } => "illegal character",
+            UriError::InvalidAuthorityPath { .. } => "expected '/'",
+            UriError::InvalidPathWithoutAuthority { .. } => "'//' without an authority",
+            UriError::InvalidPathSegment { .. } => "invalid segment",
+            UriError::UnsupportedDevicePath { .. } => "unsupported device",
+            UriError::RelativeFilePath { .. } => "relative path",
+            UriError::WorkingDirectoryUnavailable { .. } => "relative path",
+            UriError::MissingAuthority { .. } => "missing authority",
+            UriError::InvalidGlobPattern { .. } => "invalid pattern",
+            UriError::InvalidPercentEncoding { .. } => "invalid escape",
+        }
+    }
+
+    #[cfg(feature = "miette")]
+    fn code(&self) -> &'static str {
+        match self {
+            UriError::MissingScheme { .. } => "vscode_uri::missing_scheme",
+            UriError::IllegalSchemeCharacters { .. } => "vscode_uri::illegal_scheme_characters",
+            UriError::InvalidAuthorityPath { .. } => "vscode_uri::invalid_authority_path",
+            UriError::InvalidPathWithoutAuthority { .. } => {
+                "vscode_uri::invalid_path_without_authority"
+            }
+            UriError::InvalidPathSegment { .. } => "vscode_uri::invalid_path_segment",
+            UriError::UnsupportedDevicePath { .. } => "vscode_uri::unsupported_device_path",
+            UriError::RelativeFilePath { .. } => "vscode_uri::relative_file_path",
+            UriError::WorkingDirectoryUnavailable { .. } => {
+                "vscode_uri::working_directory_unavailable"
+            }
+            UriError::MissingAuthority { .. } => "vscode_uri::missing_authority",
+            UriError::InvalidGlobPattern { .. } => "vscode_uri::invalid_glob_pattern",
+            UriError::InvalidPercentEncoding { .. } => "vscode_uri::invalid_percent_encoding",
+        }
+    }
+}
+
+// Renders the error as a diagnostic that points at `span` in `input`, labeled with the problem
+// and the component.
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for UriError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new(UriError::code(self)))
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        Some(&self.context().input)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        let label = format!("{} in the {}", self.label(), self.component());
+        Some(Box::new(core::iter::once(
+            miette::LabeledSpan::new_with_span(Some(label), self.span()),
+        )))
+    }
+}
+
+// Like `percent_decode`'s escapes: '%' followed by two alphanumeric characters.
+fn is_escape_at(bytes: &[u8], idx: usize) -> bool {
+    bytes[idx] == b'%'
+        && bytes.get(idx + 1).is_some_and(u8::is_ascii_alphanumeric)
+        && bytes.get(idx + 2).is_some_and(u8::is_ascii_alphanumeric)
+}
+
+// Maps an offset in the decoded text of a component to an offset in its original text `raw`.
+fn raw_offset(raw: &str, decoded_offset: usize) -> usize {
+    let bytes = raw.as_bytes();
+    let (mut raw_pos, mut decoded_pos) = (0, 0);
+    while raw_pos < bytes.len() && decoded_pos < decoded_offset {
+        raw_pos += if is_escape_at(bytes, raw_pos) { 3 } else { 1 };
+        decoded_pos += 1;
+    }
+    let mut raw_pos = raw_pos.min(raw.len());
+    while !raw.is_char_boundary(raw_pos) {
+        raw_pos += 1;
+    }
+    raw_pos
+}
+
+impl core::error::Error for UriError {}
+
+fn validate_uri(uri: &URI, rules: &SchemeRules, strict: bool) -> Result<(), UriError> {
     if uri.scheme.is_empty() && strict {
         return Err(UriError::MissingScheme {
             scheme: uri.scheme.clone(),
@@ -94,24 +473,71 @@ fn validate_uri(uri: &URI, strict: bool) -> Result<(), UriError> {
             path: uri.path.clone(),
             query: uri.query.clone(),
             fragment: uri.fragment.clone(),
+            context: Box::new(ErrorContext {
+                input: uri.scheme.clone(),
+                span: 0..0,
+                component: UriComponent::Scheme,
+            }),
         });
     }
 
-    if !uri.scheme.is_empty() && !SCHEME_PATTERN.is_match(&uri.scheme.to_lowercase()) {
-        return Err(UriError::IllegalSchemeCharacters);
+    if !uri.scheme.is_empty() && !is_valid_scheme(&uri.scheme.to_lowercase()) {
+        return Err(UriError::IllegalSchemeCharacters {
+            context: Box::new(ErrorContext {
+                input: uri.scheme.clone(),
+                span: illegal_scheme_character(&uri.scheme),
+                component: UriComponent::Scheme,
+            }),
+        });
+    }
+
+    if rules.requires_authority && uri.authority.is_empty() {
+        return Err(UriError::MissingAuthority {
+            scheme: uri.scheme.clone(),
+            context: Box::new(ErrorContext {
+                input: uri.authority.clone(),
+                span: 0..0,
+                component: UriComponent::Authority,
+            }),
+        });
     }
 
     if !uri.path.is_empty() {
-        if !uri.authority.is_empty() && !SINGLE_SLASH_START.is_match(&uri.path) {
-            return Err(UriError::InvalidAuthorityPath);
+        if !uri.authority.is_empty() && !uri.path.starts_with('/') {
+            let first = uri.path.chars().next().map_or(0, char::len_utf8);
+            return Err(UriError::InvalidAuthorityPath {
+                context: Box::new(ErrorContext {
+                    input: uri.path.clone(),
+                    span: 0..first,
+                    component: UriComponent::Path,
+                }),
+            });
         }
-        if uri.authority.is_empty() && DOUBLE_SLASH_START.is_match(&uri.path) {
-            return Err(UriError::InvalidPathWithoutAuthority);
+        if uri.authority.is_empty() && uri.path.starts_with("//") {
+            return Err(UriError::InvalidPathWithoutAuthority {
+                context: Box::new(ErrorContext {
+                    input: uri.path.clone(),
+                    span: 0..2,
+                    component: UriComponent::Path,
+                }),
+            });
         }
     }
     Ok(())
 }
 
+// The first character of `scheme` that `is_valid_scheme` rejects.
+fn illegal_scheme_character(scheme: &str) -> Range<usize> {
+    let illegal = scheme.char_indices().find(|(idx, c)| {
+        if *idx == 0 {
+            !c.is_ascii_alphabetic()
+        } else {
+            !c.is_ascii_alphanumeric() && !matches!(c, '+' | '.' | '-')
+        }
+    });
+    illegal.map_or(0..scheme.len(), |(idx, c)| idx..idx + c.len_utf8())
+}
+
 fn scheme_fix(scheme: &str, strict: bool) -> String {
     if scheme.is_empty() && !strict {
         return "file".to_string();
@@ -119,26 +545,29 @@ fn scheme_fix(scheme: &str, strict: bool) -> String {
     scheme.to_string()
 }
 
-fn reference_resolution(scheme: &str, path: &str) -> String {
-    match scheme {
-        "https" | "http" | "file" => {
-            if path.is_empty() {
-                SLASH.to_string()
-            } else if !path.starts_with(SLASH) {
-                format!("{}{}", SLASH, path)
-            } else {
-                path.to_string()
-            }
+fn reference_resolution(rules: &SchemeRules, path: &str) -> String {
+    if rules.absolute_path {
+        if path.is_empty() {
+            SLASH.to_string()
+        } else if !path.starts_with(SLASH) {
+            format!("{}{}", SLASH, path)
+        } else {
+            path.to_string()
         }
-        _ => path.to_string(),
+    } else {
+        path.to_string()
     }
 }
 
-fn encode_uri_component_fast(uri_component: &str, is_path: bool, is_authority: bool) -> String {
+pub(crate) fn encode_uri_component_fast(
+    uri_component: &str,
+    is_path: bool,
+    is_authority: bool,
+) -> String {
     let mut res: Option<String> = None;
     let mut native_encode_pos: i32 = -1;
 
-    for (i, c) in uri_component.chars().enumerate() {
+    for (i, c) in uri_component.char_indices() {
         let code = c as u32;
 
         // unreserved characters: https://tools.ietf.org/html/rfc3986#section-2.3
@@ -157,8 +586,8 @@ fn encode_uri_component_fast(uri_component: &str, is_path: bool, is_authority: b
             // check if we are delaying native encode
             if native_encode_pos != -1 {
                 let encoded = percent_encode(
-                    uri_component[native_encode_pos as usize..i].as_bytes(),
-                    CONTROLS,
+                    &uri_component.as_bytes()[native_encode_pos as usize..i],
+                    URI_COMPONENT,
                 )
                 .to_string()
                 .to_uppercase();
@@ -182,15 +611,15 @@ fn encode_uri_component_fast(uri_component: &str, is_path: bool, is_authority: b
             let escaped = if code == CharCode::Backslash as u32 && is_path {
                 Some("%5C")
             } else {
-                ENCODE_TABLE.get(&code).copied()
+                encode_table(code)
             };
 
             if let Some(escaped) = escaped {
                 // check if we are delaying native encode
                 if native_encode_pos != -1 {
                     let encoded = percent_encode(
-                        uri_component[native_encode_pos as usize..i].as_bytes(),
-                        CONTROLS,
+                        &uri_component.as_bytes()[native_encode_pos as usize..i],
+                        URI_COMPONENT,
                     )
                     .to_string()
                     .to_uppercase();
@@ -204,14 +633,6 @@ fn encode_uri_component_fast(uri_component: &str, is_path: bool, is_authority: b
 
                 // append escaped variant to result
                 res.as_mut().unwrap().push_str(escaped);
-            } else if code > 127 {
-                // Always encode non-ASCII characters
-                let bytes = c.to_string().as_bytes().to_vec();
-                let encoded = bytes
-                    .iter()
-                    .map(|b| format!("%{:02X}", b))
-                    .collect::<String>();
-                res.as_mut().unwrap().push_str(&encoded);
             } else if native_encode_pos == -1 {
                 // use native encode only when needed
                 native_encode_pos = i as i32;
@@ -221,8 +642,8 @@ fn encode_uri_component_fast(uri_component: &str, is_path: bool, is_authority: b
 
     if native_encode_pos != -1 {
         let encoded = percent_encode(
-            uri_component[native_encode_pos as usize..].as_bytes(),
-            CONTROLS,
+            &uri_component.as_bytes()[native_encode_pos as usize..],
+            URI_COMPONENT,
         )
         .to_string()
         .to_uppercase();
@@ -249,109 +670,206 @@ fn encode_uri_component_minimal(uri_component: &str) -> String {
     res
 }
 
+// Maps the forms of `file:` URIs that RFC 8089 allows onto the form `URI::file` creates:
+// `file://localhost/etc` (unless `keep_localhost`) is `file:///etc` and the legacy
+// `file:///C|/path` is `file:///C:/path`. `file:/etc` and `file:c:/path` need no mapping.
+fn normalize_file_uri(authority: &mut String, path: &mut String, keep_localhost: bool) {
+    if !keep_localhost && authority.eq_ignore_ascii_case("localhost") {
+        authority.clear();
+    }
+    if let Some(fixed) = fix_legacy_drive(path) {
+        *path = fixed;
+    }
+}
+
+// Replaces the '|' of a legacy drive letter (`C|/path` or `/C|/path`) with ':'.
+fn fix_legacy_drive(path: &str) -> Option<String> {
+    let start = usize::from(path.starts_with('/'));
+    let bytes = path.as_bytes();
+    let is_drive = bytes.get(start).is_some_and(u8::is_ascii_alphabetic)
+        && bytes.get(start + 1) == Some(&b'|')
+        && bytes.get(start + 2).is_none_or(|b| *b == b'/');
+    is_drive.then(|| format!("{}:{}", &path[..start + 1], &path[start + 2..]))
+}
+
+// Whether `URI::file` takes `path` for an absolute path rather than a path below the root.
+// Like in `URI::file`, drive letters are recognized on all platforms.
+#[cfg(feature = "std")]
+fn is_absolute_file_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    path.starts_with('/')
+        || (is_windows() && path.starts_with('\\'))
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+#[cfg(feature = "std")]
+fn is_drive_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    bytes.len() >= 2
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes.len() == 2 || bytes[2] == b'/' || bytes[2] == b'\\')
+}
+
+// Maps the Win32 file (`//?/`) and device (`//./`) namespaces onto plain drive or UNC
+// paths. `path` must already use forward slashes.
+#[cfg(feature = "std")]
+fn strip_win32_namespace(path: &str) -> Option<String> {
+    let (rest, is_file_namespace) = match path.strip_prefix("//?/") {
+        Some(rest) => (rest, true),
+        None => match path.strip_prefix("//./") {
+            Some(rest) => (rest, false),
+            None => return Some(path.to_string()),
+        },
+    };
+
+    if is_drive_path(rest) {
+        return Some(rest.to_string());
+    }
+    if is_file_namespace
+        && rest
+            .get(..4)
+            .is_some_and(|p| p.eq_ignore_ascii_case("UNC/"))
+    {
+        return Some(format!("//{}", &rest[4..]));
+    }
+    None
+}
+
+#[cfg(feature = "std")]
 fn uri_to_fs_path(uri: &URI, keep_drive_letter_casing: bool) -> String {
-    let mut value: String;
+    let mut value = fs_path_text(uri, &uri.path, keep_drive_letter_casing);
+    value = match uri.path_bytes {
+        Some(PathBytes::Escaped(_)) => {
+            String::from_utf8_lossy(&unescape_path_bytes(&value)).into_owned()
+        }
+        _ => percent_decode(&value),
+    };
 
-    if !uri.authority.is_empty() && uri.path.len() > 1 && uri.scheme == "file" {
-        value = format!("//{}{}", uri.authority, uri.path);
-    } else if uri.path.chars().next() == Some('/')
-        && ((uri
-            .path
-            .chars()
-            .nth(1)
-            .map_or(false, |c| c.is_ascii_uppercase())
-            && uri.path.chars().nth(2) == Some(':'))
-            || (uri
-                .path
-                .chars()
-                .nth(1)
-                .map_or(false, |c| c.is_ascii_lowercase())
-                && uri.path.chars().nth(2) == Some(':')))
+    if is_windows() {
+        value = value.replace('/', "\\");
+        // `MAX_PATH` counts UTF-16 code units
+        if value.encode_utf16().count() >= WIN32_MAX_PATH {
+            if let Some(unc) = value.strip_prefix("\\\\") {
+                value = format!("\\\\?\\UNC\\{}", unc);
+            } else if is_drive_path(&value) {
+                value = format!("\\\\?\\{}", value);
+            }
+        }
+    }
+
+    value
+}
+
+// The file system path of `uri` with forward slashes for its path `path` (e.g. the escaped form
+// of `uri.path`), before the path is decoded.
+#[cfg(feature = "std")]
+fn fs_path_text(uri: &URI, path: &str, keep_drive_letter_casing: bool) -> String {
+    // `file:///C|/path`, see `normalize_file_uri`
+    let legacy_drive = fix_legacy_drive(path).filter(|_| uri.scheme == "file");
+    let path = legacy_drive.as_deref().unwrap_or(path);
+
+    if !uri.authority.is_empty() && path.len() > 1 && uri.scheme == "file" {
+        format!("//{}{}", uri.authority, path)
+    } else if path.starts_with('/')
+        && path.chars().nth(1).is_some_and(|c| c.is_ascii_alphabetic())
+        && path.chars().nth(2) == Some(':')
     {
         if !keep_drive_letter_casing {
-            value = format!(
+            format!(
                 "{}{}",
-                uri.path
-                    .chars()
-                    .nth(1)
-                    .unwrap()
-                    .to_lowercase()
-                    .next()
-                    .unwrap(),
-                uri.path.chars().skip(2).collect::<String>()
-            );
+                path.chars().nth(1).unwrap().to_lowercase().next().unwrap(),
+                path.chars().skip(2).collect::<String>()
+            )
         } else {
-            value = uri.path.chars().skip(1).collect();
+            path.chars().skip(1).collect()
         }
     } else {
-        value = uri.path.clone();
+        path.to_string()
     }
+}
 
-    value = percent_decode(&value).to_string();
-
-    if is_windows() {
-        value = value.replace('/', "\\");
+// Formats an authority, encoding the user information and the (lowercased) host, but not the port.
+pub(crate) fn format_authority(
+    authority: &str,
+    encoder: &impl Fn(&str, Component) -> String,
+) -> String {
+    let mut res = String::new();
+    let mut authority = authority.to_string();
+    if let Some(idx) = authority.find('@') {
+        let userinfo = authority[..idx].to_string();
+        authority = authority[idx + 1..].to_string();
+        if let Some(idx) = userinfo.rfind(':') {
+            res.push_str(&encoder(&userinfo[..idx], Component::User));
+            res.push(':');
+            res.push_str(&encoder(&userinfo[idx + 1..], Component::Password));
+        } else {
+            res.push_str(&encoder(&userinfo, Component::User));
+        }
+        res.push('@');
     }
-
-    value
+    authority = authority.to_lowercase();
+    if let Some(idx) = authority.rfind(':') {
+        res.push_str(&encoder(&authority[..idx], Component::Host));
+        res.push_str(&authority[idx..]);
+    } else {
+        res.push_str(&encoder(&authority, Component::Host));
+    }
+    res
 }
 
-fn as_formatted(uri: &URI, skip_encoding: bool) -> String {
+fn as_formatted(uri: &URI, skip_encoding: bool, options: &EncodeOptions) -> String {
     let mut res = String::new();
 
-    let encoder = if !skip_encoding {
-        |s: &str, is_path: bool, is_authority: bool| {
-            if is_path && is_windows() {
-                encode_uri_component_fast(&s.replace('\\', "%5C"), is_path, is_authority)
+    let encoder = |s: &str, component: Component| {
+        if skip_encoding {
+            return encode_uri_component_minimal(s);
+        }
+        let set = options.set_for(component);
+        let encode = |s: &str| {
+            if component == Component::Path && set == EncodingSet::VsCode && is_windows() {
+                set.encode_for_scheme(&s.replace('\\', "%5C"), component, &uri.scheme)
             } else {
-                encode_uri_component_fast(s, is_path, is_authority)
+                set.encode_for_scheme(s, component, &uri.scheme)
+            }
+        };
+        match component {
+            Component::Path if uri.encoded_delimiters => encode_keeping_delimiters(s, &[], encode),
+            Component::Query | Component::Fragment if uri.encoded_delimiters => {
+                encode_keeping_delimiters(s, &['/', '?'], encode)
             }
+            _ => encode(s),
         }
-    } else {
-        |s: &str, _is_path: bool, _is_authority: bool| encode_uri_component_minimal(s)
     };
 
-    if !uri.scheme.is_empty() {
+    // the original text is only reproduced with the default encoding
+    let raw = uri
+        .raw
+        .as_deref()
+        .filter(|_| !skip_encoding && *options == EncodeOptions::default());
+
+    if let Some(raw) = raw.and_then(|raw| raw.scheme.as_deref()) {
+        res.push_str(raw);
+    } else if !uri.scheme.is_empty() {
         res.push_str(&uri.scheme);
         res.push(':');
     }
 
-    if !uri.authority.is_empty() || uri.scheme == "file" {
-        res.push_str("//");
-    }
+    if let Some(raw) = raw.and_then(|raw| raw.authority.as_deref()) {
+        res.push_str(raw);
+    } else {
+        if !uri.authority.is_empty() || SchemeRegistry::rules(&uri.scheme).authority_slashes {
+            res.push_str("//");
+        }
 
-    if !uri.authority.is_empty() {
-        let mut authority = uri.authority.clone();
-        if let Some(idx) = authority.find('@') {
-            let userinfo = authority[..idx].to_string();
-            authority = authority[idx + 1..].to_string();
-            if let Some(idx) = userinfo.rfind(':') {
-                res.push_str(&encoder(&userinfo[..idx], false, false));
-                res.push(':');
-                res.push_str(&encoder(&userinfo[idx + 1..], false, true));
-            } else {
-                res.push_str(&encoder(&userinfo, false, false));
-            }
-            res.push('@');
-            authority = authority.to_lowercase();
-            if let Some(idx) = authority.rfind(':') {
-                res.push_str(&encoder(&authority[..idx], false, true));
-                res.push_str(&authority[idx..]);
-            } else {
-                res.push_str(&encoder(&authority, false, true));
-            }
-        } else {
-            authority = authority.to_lowercase();
-            if let Some(idx) = authority.rfind(':') {
-                res.push_str(&encoder(&authority[..idx], false, true));
-                res.push_str(&authority[idx..]);
-            } else {
-                res.push_str(&encoder(&authority, false, true));
-            }
+        if !uri.authority.is_empty() {
+            res.push_str(&format_authority(&uri.authority, &encoder));
         }
     }
 
-    if !uri.path.is_empty() {
+    if let Some(raw) = raw.and_then(|raw| raw.path.as_deref()) {
+        res.push_str(raw);
+    } else if !uri.path.is_empty() {
         let mut path = uri.path.clone();
         if path.len() >= 3 {
             let mut chars = path.chars();
@@ -373,10 +891,22 @@ fn as_formatted(uri: &URI, skip_encoding: bool) -> String {
                 }
             }
         }
-        res.push_str(&encoder(&path, true, false));
+        if matches!(uri.path_bytes, Some(PathBytes::Escaped(_))) && !skip_encoding {
+            // a path that is not UTF-8 is encoded from its bytes
+            for chunk in unescape_path_bytes(&path).utf8_chunks() {
+                res.push_str(&encoder(chunk.valid(), Component::Path));
+                for b in chunk.invalid() {
+                    res.push_str(&format!("%{:02X}", b));
+                }
+            }
+        } else {
+            res.push_str(&encoder(&path, Component::Path));
+        }
     }
 
-    if !uri.query.is_empty() {
+    if let Some(raw) = raw.and_then(|raw| raw.query.as_deref()) {
+        res.push_str(raw);
+    } else if !uri.query.is_empty() {
         res.push('?');
         if skip_encoding {
             let mut query = String::new();
@@ -390,14 +920,16 @@ fn as_formatted(uri: &URI, skip_encoding: bool) -> String {
             }
             res.push_str(&query);
         } else {
-            res.push_str(&encoder(&uri.query, false, false));
+            res.push_str(&encoder(&uri.query, Component::Query));
         }
     }
 
-    if !uri.fragment.is_empty() {
+    if let Some(raw) = raw.and_then(|raw| raw.fragment.as_deref()) {
+        res.push_str(raw);
+    } else if !uri.fragment.is_empty() {
         res.push('#');
         if !skip_encoding {
-            res.push_str(&encode_uri_component_fast(&uri.fragment, false, false));
+            res.push_str(&encoder(&uri.fragment, Component::Fragment));
         } else {
             res.push_str(&uri.fragment);
         }
@@ -406,6 +938,30 @@ fn as_formatted(uri: &URI, skip_encoding: bool) -> String {
     res
 }
 
+// Lowercases the host and drops an empty or default port, keeping the user information as is.
+fn normalize_authority(authority: &str, default_port: Option<u16>) -> String {
+    let (userinfo, host_port) = match authority.rfind('@') {
+        Some(idx) => (&authority[..=idx], &authority[idx + 1..]),
+        None => ("", authority),
+    };
+    let (host, port) = match host_port.rfind(':') {
+        Some(idx)
+            if !host_port[idx + 1..].contains(']')
+                && host_port[idx + 1..].bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            (&host_port[..idx], &host_port[idx + 1..])
+        }
+        _ => (host_port, ""),
+    };
+
+    let mut res = format!("{}{}", userinfo, host.to_lowercase());
+    if !port.is_empty() && default_port.map(|p| p.to_string()).as_deref() != Some(port) {
+        res.push(':');
+        res.push_str(port);
+    }
+    res
+}
+
 fn decode_uri_component_graceful(str: &str) -> String {
     if str.len() < 3 {
         return str.to_string();
@@ -419,29 +975,196 @@ fn decode_uri_component_graceful(str: &str) -> String {
     }
 }
 
-fn percent_decode(str: &str) -> String {
-    if !ENCODED_AS_HEX.is_match(str) {
+// Decodes each run of `%` followed by two ASCII letters or digits, i.e. `(%[0-9A-Za-z][0-9A-Za-z])+`.
+pub(crate) fn percent_decode(str: &str) -> String {
+    let bytes = str.as_bytes();
+
+    let mut result = String::new();
+    let mut last_end = 0;
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if !is_escape_at(bytes, idx) {
+            idx += 1;
+            continue;
+        }
+        let start = idx;
+        while idx < bytes.len() && is_escape_at(bytes, idx) {
+            idx += 3;
+        }
+        result.push_str(&str[last_end..start]);
+        result.push_str(&decode_uri_component_graceful(&str[start..idx]));
+        last_end = idx;
+    }
+    if last_end == 0 {
         return str.to_string();
     }
+    result.push_str(&str[last_end..]);
+    result
+}
 
+// The escapes `ParseOptions::keep_encoded_delimiters` keeps, i.e. of '/', '?', '#' and '%'.
+fn encoded_delimiter_at(str: &str, idx: usize) -> Option<&'static str> {
+    let escape = str.get(idx..idx + 3)?;
+    ["%2F", "%3F", "%23", "%25"]
+        .into_iter()
+        .find(|delimiter| escape.eq_ignore_ascii_case(delimiter))
+}
+
+// Like `percent_decode`, but keeps encoded delimiters, in upper case.
+fn percent_decode_keeping_delimiters(str: &str) -> String {
     let mut result = String::new();
     let mut last_end = 0;
-    for caps in ENCODED_AS_HEX.find_iter(str) {
-        result.push_str(&str[last_end..caps.start()]);
-        result.push_str(&decode_uri_component_graceful(caps.as_str()));
-        last_end = caps.end();
+    for (idx, _) in str.match_indices('%') {
+        if idx < last_end {
+            continue;
+        }
+        if let Some(delimiter) = encoded_delimiter_at(str, idx) {
+            result.push_str(&percent_decode(&str[last_end..idx]));
+            result.push_str(delimiter);
+            last_end = idx + 3;
+        }
     }
-    result.push_str(&str[last_end..]);
+    result.push_str(&percent_decode(&str[last_end..]));
     result
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+// Encodes a component of a URI that keeps encoded delimiters: the kept escapes and the
+// literal `delimiters` are copied, everything in between is encoded with `encode`.
+fn encode_keeping_delimiters(
+    str: &str,
+    delimiters: &[char],
+    encode: impl Fn(&str) -> String,
+) -> String {
+    let mut result = String::new();
+    let mut last_end = 0;
+    for (idx, c) in str.char_indices() {
+        if idx < last_end {
+            continue;
+        }
+        let kept = match encoded_delimiter_at(str, idx) {
+            Some(delimiter) => delimiter,
+            None if delimiters.contains(&c) => &str[idx..idx + 1],
+            None => continue,
+        };
+        result.push_str(&encode(&str[last_end..idx]));
+        result.push_str(kept);
+        last_end = idx + kept.len();
+    }
+    result.push_str(&encode(&str[last_end..]));
+    result
+}
+
+// The path of a URI for a path that is not UTF-8, e.g. of a file name on Unix: bytes that are
+// not UTF-8 are written as `%XX`, like `parse` keeps escapes it cannot decode, and a '%' that
+// would read as an escape as `%25`, so that `unescape_path_bytes` returns the bytes.
+#[cfg(feature = "std")]
+fn escape_path_bytes(bytes: &[u8]) -> String {
+    let mut result = String::new();
+    for chunk in bytes.utf8_chunks() {
+        let valid = chunk.valid();
+        for (idx, c) in valid.char_indices() {
+            if c == '%' && is_hex_escape_at(valid.as_bytes(), idx) {
+                result.push_str("%25");
+            } else {
+                result.push(c);
+            }
+        }
+        for b in chunk.invalid() {
+            result.push_str(&format!("%{:02X}", b));
+        }
+    }
+    result
+}
+
+// Reverses `escape_path_bytes`: decodes `%25` and the escapes of bytes that are not ASCII,
+// keeping everything else as is.
+fn unescape_path_bytes(path: &str) -> Vec<u8> {
+    let bytes = path.as_bytes();
+    let mut result = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if is_hex_escape_at(bytes, idx) {
+            let hex = |b: u8| (b as char).to_digit(16).unwrap() as u8;
+            let b = hex(bytes[idx + 1]) << 4 | hex(bytes[idx + 2]);
+            if b == b'%' || !b.is_ascii() {
+                result.push(b);
+                idx += 3;
+                continue;
+            }
+        }
+        result.push(bytes[idx]);
+        idx += 1;
+    }
+    result
+}
+
+fn is_hex_escape_at(bytes: &[u8], idx: usize) -> bool {
+    bytes[idx] == b'%'
+        && bytes.get(idx + 1).is_some_and(u8::is_ascii_hexdigit)
+        && bytes.get(idx + 2).is_some_and(u8::is_ascii_hexdigit)
+}
+
+#[derive(Debug, Clone)]
 pub struct URI {
     scheme: String,
     authority: String,
     path: String,
     query: String,
     fragment: String,
+    // whether the path, query and fragment keep `%2F`, `%3F`, `%23` and `%25`, see `ParseOptions`
+    encoded_delimiters: bool,
+    // the original text of the components, see `URI::parse_preserving`
+    raw: Option<Box<RawComponents>>,
+    // the bytes of a path that is not UTF-8
+    path_bytes: Option<PathBytes>,
+}
+
+// The bytes of a `file:` path that is not UTF-8.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum PathBytes {
+    // from `URI::file`: `path` is their escaped form (see `escape_path_bytes`) and `to_string`
+    // writes the bytes
+    Escaped(Box<[u8]>),
+    // from `parse`: `path` is decoded like in vscode-uri, and only `fs_path_os` returns the bytes
+    Decoded(Box<[u8]>),
+}
+
+// The original text of each component including its delimiter (e.g. `http:`, `//host` or `?a=%2F`),
+// or an empty string if the component was absent. `None` once a component has been changed.
+#[derive(Debug, Clone, Default)]
+struct RawComponents {
+    scheme: Option<String>,
+    authority: Option<String>,
+    path: Option<String>,
+    query: Option<String>,
+    fragment: Option<String>,
+}
+
+// The original text does not take part in comparisons: a preserved URI equals the URI `parse` returns.
+impl PartialEq for URI {
+    fn eq(&self, other: &Self) -> bool {
+        self.scheme == other.scheme
+            && self.authority == other.authority
+            && self.path == other.path
+            && self.query == other.query
+            && self.fragment == other.fragment
+            && self.encoded_delimiters == other.encoded_delimiters
+            && self.path_bytes == other.path_bytes
+    }
+}
+
+impl Eq for URI {}
+
+impl core::hash::Hash for URI {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.scheme.hash(state);
+        self.authority.hash(state);
+        self.path.hash(state);
+        self.query.hash(state);
+        self.fragment.hash(state);
+        self.encoded_delimiters.hash(state);
+        self.path_bytes.hash(state);
+    }
 }
 
 impl URI {
@@ -459,7 +1182,8 @@ impl URI {
         let fragment = fragment.into();
 
         let scheme = scheme_fix(&scheme, false);
-        let path = reference_resolution(&scheme, &path);
+        let rules = SchemeRegistry::rules(&scheme);
+        let path = reference_resolution(&rules, &path);
 
         let uri = URI {
             scheme,
@@ -467,12 +1191,15 @@ impl URI {
             path,
             query,
             fragment,
+            encoded_delimiters: false,
+            raw: None,
+            path_bytes: None,
         };
-        validate_uri(&uri, false)?;
+        validate_uri(&uri, &rules, false)?;
         Ok(uri)
     }
 
-    pub fn is_uri(thing: &dyn std::any::Any) -> bool {
+    pub fn is_uri(thing: &dyn core::any::Any) -> bool {
         thing.is::<URI>()
     }
 
@@ -481,39 +1208,363 @@ impl URI {
     }
 
     pub fn parse_with_strict(value: &str, _strict: bool) -> Result<Self, UriError> {
+        Self::parse_with_options(value, &ParseOptions::default())
+    }
+
+    /**
+     * Parses an absolute URI or a relative reference such as `../a/b?x`. Unlike `parse`, which
+     * takes a value without a scheme for a `file:` path (`foo/bar` becomes `file:///foo/bar`),
+     * a relative reference is returned as a `RelativeRef` to be resolved against a base URI.
+     */
+    pub fn parse_reference(value: &str) -> Result<UriReference, UriError> {
+        let parts = UriParts::split(value);
+        if parts.scheme.is_some() {
+            return Self::parse(value).map(UriReference::Absolute);
+        }
+        Ok(UriReference::Relative(RelativeRef::from_parts(
+            value, parts,
+        )))
+    }
+
+    /**
+     * Like `parse`, with the given options.
+     */
+    pub fn parse_with_options(value: &str, options: &ParseOptions) -> Result<Self, UriError> {
         if value.is_empty() {
             return URI::new(EMPTY, EMPTY, EMPTY, EMPTY, EMPTY);
         }
 
-        if let Some(captures) = URI_REGEX.captures(value) {
-            let scheme = captures.get(2).map_or(EMPTY, |m| m.as_str());
-            let authority = captures
-                .get(4)
-                .map_or(EMPTY.to_string(), |m| percent_decode(m.as_str()));
-            let path = captures
-                .get(5)
-                .map_or(EMPTY.to_string(), |m| percent_decode(m.as_str()));
-            let query = captures
-                .get(7)
-                .map_or(EMPTY.to_string(), |m| percent_decode(m.as_str()));
-            let fragment = captures
-                .get(9)
-                .map_or(EMPTY.to_string(), |m| percent_decode(m.as_str()));
+        let parts = UriParts::split(value);
+        if options.check_escapes {
+            for (range, component) in [
+                (parts.authority.clone(), UriComponent::Authority),
+                (Some(parts.path.clone()), UriComponent::Path),
+                (parts.query.clone(), UriComponent::Query),
+                (parts.fragment.clone(), UriComponent::Fragment),
+            ] {
+                if let Some(range) = range {
+                    decode_percent(&value[range.clone()], component)
+                        .map_err(|err| err.offset_by(value, &range))?;
+                }
+            }
+        }
+        let keep = options.keep_encoded_delimiters;
+        let decode = |range: Option<Range<usize>>, keep: bool| match range {
+            Some(range) if keep => percent_decode_keeping_delimiters(&value[range]),
+            Some(range) => percent_decode(&value[range]),
+            None => EMPTY.to_string(),
+        };
+        let scheme = parts.scheme.clone().map_or(EMPTY, |range| &value[range]);
+        let mut authority = decode(parts.authority.clone(), false);
+        let mut path = decode(Some(parts.path.clone()), keep);
+        if options.normalize_file_uris && scheme.eq_ignore_ascii_case(Schemas::FILE) {
+            normalize_file_uri(&mut authority, &mut path, options.keep_localhost);
+        }
 
-            return URI::new(scheme, authority, path, query, fragment);
+        let mut uri = URI::new(
+            scheme,
+            authority,
+            path,
+            decode(parts.query.clone(), keep),
+            decode(parts.fragment.clone(), keep),
+        )
+        .map_err(|err| err.located_in(value, &parts))?;
+        uri.encoded_delimiters = keep;
+        // the path keeps the escapes of a file name that is not UTF-8 as text, like in vscode-uri,
+        // and `fs_path_os` returns the bytes they stand for
+        let path_bytes: Vec<u8> = percent_decode_str(&value[parts.path.clone()]).collect();
+        if uri.scheme == Schemas::FILE && core::str::from_utf8(&path_bytes).is_err() {
+            uri.path_bytes = Some(PathBytes::Decoded(path_bytes.into_boxed_slice()));
         }
+        Ok(uri)
+    }
 
-        URI::new(EMPTY, EMPTY, EMPTY, EMPTY, EMPTY)
+    /**
+     * Like `parse`, but fails with `UriError::InvalidPercentEncoding` instead of keeping
+     * malformed escapes (e.g. `%zz` or `%E`) or escapes of invalid UTF-8 (e.g. `%E0%A4`) as is.
+     * The span of the error is relative to `value`. This is unrelated to the `strict` flag of
+     * `parse_with_strict`, which is about the scheme. To combine it with other options, use
+     * `parse_with_options` with `ParseOptions::check_escapes`.
+     */
+    pub fn parse_checked_escapes(value: &str) -> Result<Self, UriError> {
+        Self::parse_with_options(
+            value,
+            &ParseOptions {
+                check_escapes: true,
+                ..Default::default()
+            },
+        )
     }
 
+    /**
+     * Like `parse`, but keeps the original text of the authority, path, query and fragment.
+     * `to_string(false)` reproduces that text byte-for-byte instead of re-encoding the components,
+     * e.g. to keep the query of a signed URL intact. The accessors still return decoded values.
+     *
+     * Components that are changed with `with` (or the helpers built on it) are encoded as usual,
+     * and so are the authority and the path after a changed scheme or authority, and components
+     * whose text is not what they are parsed from, such as the missing scheme of `/no/scheme`.
+     * `normalized` drops the original text altogether.
+     */
+    pub fn parse_preserving(value: &str) -> Result<Self, UriError> {
+        let mut uri = Self::parse(value)?;
+        let parts = UriParts::split(value);
+        // the ranges exclude the delimiters, which are part of the original text
+        let raw = |range: Option<Range<usize>>, before: usize, after: usize| {
+            Some(range.map_or(EMPTY.to_string(), |range| {
+                value[range.start - before..range.end + after].to_string()
+            }))
+        };
+        uri.raw = Some(Box::new(RawComponents {
+            scheme: raw(parts.scheme, 0, 1),
+            authority: raw(parts.authority, 2, 0),
+            path: raw(Some(parts.path), 0, 0),
+            query: raw(parts.query, 1, 0),
+            fragment: raw(parts.fragment, 1, 0),
+        }));
+        Ok(uri)
+    }
+
+    /**
+     * Creates a new URI from a file system path, e.g. `c:\my\files`, `/usr/home`, or `\\server\share\some\path`.
+     * On Windows, `\\?\C:\...`, `\\?\UNC\server\share\...` and `\\.\C:\...` are mapped onto their plain form;
+     * other device namespace paths (e.g. `\\.\pipe\...`) fail with `UriError::UnsupportedDevicePath`.
+     *
+     * Like in vscode-uri, a relative path is taken for a path below the root (`src/main.rs` is
+     * `file:///src/main.rs`); use `file_relative_to`, `file_with_options` or `file_strict` instead.
+     *
+     * On Unix, a file name that is not UTF-8 is kept as bytes: the path shows them as `%XX` (and
+     * a '%' that would read as an escape as `%25`), `to_string` encodes them as `%XX` and `parse`
+     * reads such escapes of a `file:` URI back into the bytes, so `file:///a/%FF` is the file
+     * `/a/` followed by the byte `0xFF`, not the file `/a/%FF`.
+     */
+    #[cfg(feature = "std")]
     pub fn file(path: impl AsRef<Path>) -> Result<Self, UriError> {
         let path = path.as_ref();
-        let mut path_str = path.to_string_lossy().to_string();
+        #[cfg(all(feature = "std", unix))]
+        if path.to_str().is_none() {
+            return Self::file_from_bytes(path);
+        }
+        Self::file_from_str(path.to_string_lossy().to_string(), path)
+    }
+
+    /**
+     * Creates a URI from a command line argument that is either a URI or a file system path,
+     * like VS Code does for the arguments of `code`:
+     *  - `\\server\share\a.ts` and `//server/share/a.ts` are UNC paths,
+     *  - `C:\work\a.ts` and `c:/work/a.ts` are Windows paths, also on other platforms,
+     *  - a value with a scheme of two or more characters, e.g. `https://host/a.ts`, is a URI,
+     *  - `/home/me/a.ts` is an absolute path,
+     *  - anything else, e.g. `./a.ts`, is a path relative to `cwd`.
+     *
+     * Returns the URI and the interpretation that was chosen.
+     */
+    #[cfg(feature = "std")]
+    pub fn parse_or_file(
+        value: &str,
+        cwd: impl AsRef<Path>,
+    ) -> Result<(Self, UriInterpretation), UriError> {
+        let bytes = value.as_bytes();
+        let is_separator = |b: u8| b == b'/' || b == b'\\';
+        if bytes.len() >= 2 && is_separator(bytes[0]) && is_separator(bytes[1]) {
+            let uri = Self::file(value.replace('\\', SLASH))?;
+            return Ok((uri, UriInterpretation::UncPath));
+        }
+        if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+            let uri = Self::file(value.replace('\\', SLASH))?;
+            return Ok((uri, UriInterpretation::DrivePath));
+        }
+
+        // a single letter is a drive letter rather than a scheme
+        let scheme = UriParts::split(value).scheme.map(|range| &value[range]);
+        if scheme.is_some_and(|scheme| scheme.len() > 1 && is_valid_scheme(scheme)) {
+            return Ok((Self::parse(value)?, UriInterpretation::Uri));
+        }
 
+        let path = if is_windows() {
+            value.replace('\\', SLASH)
+        } else {
+            value.to_string()
+        };
+        if path.starts_with(SLASH) {
+            return Ok((Self::file(path)?, UriInterpretation::AbsolutePath));
+        }
+        let uri = Self::file_relative_to(path, &Self::file(cwd.as_ref())?)?;
+        Ok((uri, UriInterpretation::RelativePath))
+    }
+
+    /**
+     * Like `file`, but resolves a relative path (e.g. `src/main.rs` or `../a.ts`) against the path
+     * of `base` instead of taking it for a path below the root. Absolute paths ignore `base`.
+     * Like on Windows, `..` stops at the root of a drive: `../../x` from `/c:/work` is `/c:/x`.
+     */
+    #[cfg(feature = "std")]
+    pub fn file_relative_to(path: impl AsRef<Path>, base: &URI) -> Result<Self, UriError> {
+        let path = path.as_ref();
+        if is_absolute_file_path(&path.to_string_lossy()) {
+            return Self::file(path);
+        }
+        // like in `URI::file`, a path that is not UTF-8 is resolved in its escaped form, and so is
+        // the path of `base`, as well as any path against a `base` that is not UTF-8
+        #[cfg(unix)]
+        let bytes = {
+            use std::os::unix::ffi::OsStrExt;
+            let escaped = base.path_bytes.is_some() && !is_windows();
+            (path.to_str().is_none() || escaped).then(|| path.as_os_str().as_bytes())
+        };
+        #[cfg(not(unix))]
+        let bytes: Option<&[u8]> = None;
+        let mut relative = match bytes {
+            Some(bytes) => escape_path_bytes(bytes),
+            None => path.to_string_lossy().to_string(),
+        };
+        if is_windows() {
+            relative = relative.replace('\\', SLASH);
+        }
+        let mut uri = base.clone();
+        if bytes.is_some() {
+            uri.path = uri.escaped_path();
+        }
+        let mut uri = uri.resolve_below_drive(&relative)?;
+        if bytes.is_some() && !is_windows() {
+            uri.unescape_path();
+        }
+        Ok(uri)
+    }
+
+    // Resolves `relative` against the path of this URI, stopping `..` at the root of a drive.
+    #[cfg(feature = "std")]
+    fn resolve_below_drive(&self, relative: &str) -> Result<Self, UriError> {
+        // resolve below the drive, e.g. `/work` of `/c:/work`, and put the drive back in front
+        let drive = self
+            .path
+            .get(..3)
+            .filter(|drive| drive.starts_with('/') && is_drive_path(&drive[1..]));
+        let Some(drive) = drive else {
+            return Utils::resolve_path(self, &[relative]);
+        };
+        let below_drive = self.with(URIChange {
+            path: Some(format!("/{}", self.path[3..].trim_start_matches('/'))),
+            ..Default::default()
+        })?;
+        let resolved = Utils::resolve_path(&below_drive, &[relative])?;
+        resolved.with(URIChange {
+            path: Some(format!("{}{}", drive, resolved.path)),
+            ..Default::default()
+        })
+    }
+
+    /**
+     * Like `file`, with the given options.
+     */
+    #[cfg(feature = "std")]
+    pub fn file_with_options(
+        path: impl AsRef<Path>,
+        options: &FileOptions,
+    ) -> Result<Self, UriError> {
+        let path = path.as_ref();
+        if is_absolute_file_path(&path.to_string_lossy()) {
+            return Self::file(path);
+        }
+        if options.relative_to_cwd {
+            let cwd = std::env::current_dir().map_err(|err| {
+                let path = path.to_string_lossy().to_string();
+                UriError::WorkingDirectoryUnavailable {
+                    reason: err.to_string(),
+                    context: Box::new(ErrorContext {
+                        span: 0..path.len(),
+                        input: path,
+                        component: UriComponent::Path,
+                    }),
+                }
+            })?;
+            return Self::file_relative_to(path, &Self::file(cwd)?);
+        }
+        if options.strict {
+            let path = path.to_string_lossy().to_string();
+            return Err(UriError::RelativeFilePath {
+                context: Box::new(ErrorContext {
+                    span: 0..path.len(),
+                    input: path,
+                    component: UriComponent::Path,
+                }),
+            });
+        }
+        Self::file(path)
+    }
+
+    /**
+     * Like `file`, but fails with `UriError::RelativeFilePath` if the path is relative.
+     */
+    #[cfg(feature = "std")]
+    pub fn file_strict(path: impl AsRef<Path>) -> Result<Self, UriError> {
+        Self::file_with_options(
+            path,
+            &FileOptions {
+                strict: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    // File names on Unix can be any bytes. The path of a file name that is not UTF-8 is its
+    // escaped form (see `escape_path_bytes`) and the URI keeps the bytes, so that they take part
+    // in comparisons and `to_string` and `fs_path` reproduce them.
+    #[cfg(all(feature = "std", unix))]
+    fn file_from_bytes(path: &Path) -> Result<Self, UriError> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let mut uri = Self::file_from_str(escape_path_bytes(path.as_os_str().as_bytes()), path)?;
+        if !is_windows() {
+            uri.unescape_path();
+        }
+        Ok(uri)
+    }
+
+    // Takes the path for the escaped form of a path that is not UTF-8 and keeps its bytes, or
+    // decodes it if it turns out to be UTF-8, e.g. once the file name that was not is removed.
+    fn unescape_path(&mut self) {
+        match String::from_utf8(unescape_path_bytes(&self.path)) {
+            Ok(path) => {
+                self.path = path;
+                self.path_bytes = None;
+            }
+            Err(err) => {
+                self.path_bytes = Some(PathBytes::Escaped(err.into_bytes().into_boxed_slice()))
+            }
+        }
+    }
+
+    // The escaped form of the path (see `escape_path_bytes`), with the bytes of a parsed path that
+    // is not UTF-8.
+    #[cfg(feature = "std")]
+    fn escaped_path(&self) -> String {
+        match &self.path_bytes {
+            Some(PathBytes::Escaped(_)) => self.path.clone(),
+            Some(PathBytes::Decoded(bytes)) => escape_path_bytes(bytes),
+            None => escape_path_bytes(self.path.as_bytes()),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn file_from_str(mut path_str: String, path: &Path) -> Result<Self, UriError> {
         let mut authority = String::new();
 
         if is_windows() {
             path_str = path_str.replace('\\', SLASH);
+            path_str = strip_win32_namespace(&path_str).ok_or_else(|| {
+                // the device, e.g. `\\.\pipe`
+                let end = path_str[4..]
+                    .find(SLASH)
+                    .map_or(path_str.len(), |idx| idx + 4);
+                UriError::UnsupportedDevicePath {
+                    context: Box::new(ErrorContext {
+                        input: path.to_string_lossy().to_string(),
+                        span: 0..end,
+                        component: UriComponent::Path,
+                    }),
+                }
+            })?;
         }
 
         if path_str.starts_with(SLASH) && path_str.chars().nth(1) == Some('/') {
@@ -534,14 +1585,11 @@ impl URI {
             }
         }
 
-        if path_str.len() >= 2 && path_str.chars().nth(1) == Some(':') {
-            let drive_letter = path_str
-                .chars()
-                .next()
-                .unwrap()
-                .to_lowercase()
-                .next()
-                .unwrap();
+        if path_str.len() >= 2
+            && path_str.as_bytes()[0].is_ascii_alphabetic()
+            && path_str.as_bytes()[1] == b':'
+        {
+            let drive_letter = path_str.as_bytes()[0].to_ascii_lowercase() as char;
             let rest = &path_str[2..];
             path_str = format!("/{}:{}", drive_letter, rest);
         }
@@ -575,7 +1623,95 @@ impl URI {
             return Ok(self.clone());
         }
 
-        URI::new(scheme, authority, path, query, fragment)
+        let mut uri = URI::new(&scheme, &authority, &path, &query, &fragment).map_err(|err| {
+            err.blamed_on(&[
+                (UriComponent::Scheme, scheme != self.scheme, &scheme),
+                (
+                    UriComponent::Authority,
+                    authority != self.authority,
+                    &authority,
+                ),
+                (UriComponent::Path, path != self.path, &path),
+            ])
+        })?;
+        uri.encoded_delimiters = self.encoded_delimiters;
+        // the path of a URI of a path that is not UTF-8 is changed in its escaped form, and the
+        // bytes of a parsed one are kept along with its path
+        match &self.path_bytes {
+            Some(PathBytes::Escaped(_)) => uri.unescape_path(),
+            Some(PathBytes::Decoded(_)) if uri.path == self.path && uri.scheme == Schemas::FILE => {
+                uri.path_bytes = self.path_bytes.clone();
+            }
+            _ => {}
+        }
+        if let Some(raw) = &self.raw {
+            // Keep the original text of the components that did not change and still decode to
+            // their value, e.g. not the empty text of the scheme `file` implied by `/no/scheme`.
+            // How the authority and the path are read depends on the text before them, so they
+            // are only kept along with it: `http:foo` with the authority `host` must not become
+            // `http://hostfoo`.
+            let keep = |unchanged: bool, raw: &Option<String>, value: &str, component| {
+                raw.clone()
+                    .filter(|raw| unchanged && uri.raw_decodes_to(raw, value, component))
+            };
+            let scheme = keep(
+                uri.scheme == self.scheme,
+                &raw.scheme,
+                &uri.scheme,
+                UriComponent::Scheme,
+            );
+            let authority = keep(
+                scheme.is_some() && uri.authority == self.authority,
+                &raw.authority,
+                &uri.authority,
+                UriComponent::Authority,
+            );
+            let path = keep(
+                authority.is_some() && uri.path == self.path,
+                &raw.path,
+                &uri.path,
+                UriComponent::Path,
+            );
+            let query = keep(
+                uri.query == self.query,
+                &raw.query,
+                &uri.query,
+                UriComponent::Query,
+            );
+            let fragment = keep(
+                uri.fragment == self.fragment,
+                &raw.fragment,
+                &uri.fragment,
+                UriComponent::Fragment,
+            );
+            uri.raw = Some(Box::new(RawComponents {
+                scheme,
+                authority,
+                path,
+                query,
+                fragment,
+            }));
+        }
+        Ok(uri)
+    }
+
+    // Whether the original text `raw` of a component, including its delimiter (see
+    // `RawComponents`), is parsed as `value`.
+    fn raw_decodes_to(&self, raw: &str, value: &str, component: UriComponent) -> bool {
+        let text = match component {
+            UriComponent::Scheme => return raw.strip_suffix(':').unwrap_or(raw) == value,
+            UriComponent::Authority => {
+                return percent_decode(raw.strip_prefix("//").unwrap_or(raw)) == value
+            }
+            UriComponent::Path => raw,
+            UriComponent::Query => raw.strip_prefix('?').unwrap_or(raw),
+            UriComponent::Fragment => raw.strip_prefix('#').unwrap_or(raw),
+        };
+        if self.encoded_delimiters {
+            percent_decode_keeping_delimiters(text) == value
+        } else {
+            percent_decode(text) == value
+        }
     }
 
     pub fn scheme(&self) -> &str {
@@ -598,12 +1734,212 @@ impl URI {
         &self.fragment
     }
 
+    /**
+     * Whether the path, query and fragment keep encoded delimiters, see `ParseOptions`.
+     */
+    pub fn keeps_encoded_delimiters(&self) -> bool {
+        self.encoded_delimiters
+    }
+
+    /**
+     * Returns a URI with syntax-based normalization (RFC 3986, section 6.2.2 and 6.2.3) applied,
+     * so that equivalent URIs compare equal:
+     *  - the scheme and the host are lowercased,
+     *  - an empty port or the default port of the scheme (see `SchemeRules::default_port`) is removed,
+     *  - '.' and '..' segments and sequential '/' are removed from the path,
+     *  - the path is lowercased if the scheme has `SchemeRules::case_insensitive_path`.
+     *
+     * Percent-encoding needs no normalization since components are always stored decoded.
+     */
+    pub fn normalized(&self) -> Result<Self, UriError> {
+        let scheme = self.scheme.to_lowercase();
+        let rules = SchemeRegistry::rules(&scheme);
+        let authority = normalize_authority(&self.authority, rules.default_port);
+
+        let mut path = if self.path.is_empty() {
+            String::new()
+        } else {
+            Utils::normalize_path(&self.path)
+        };
+        if path == "." {
+            path.clear();
+        }
+        if path.is_empty() && !authority.is_empty() {
+            path.push_str(SLASH);
+        }
+        if rules.case_insensitive_path {
+            path = path.to_lowercase();
+        }
+
+        let mut uri = self.with(URIChange {
+            scheme: Some(scheme),
+            authority: Some(authority),
+            path: Some(path),
+            ..Default::default()
+        })?;
+        uri.raw = None;
+        Ok(uri)
+    }
+
+    /**
+     * Returns an iterator over the (decoded) segments of the path.
+     * Empty segments, e.g. from a leading, trailing or repeated '/', are skipped.
+     * If the URI keeps encoded delimiters, `%2F` does not separate segments and the segments
+     * are returned with the delimiters still encoded, e.g. `a%2Fb` for `/a%2Fb/c`.
+     */
+    pub fn segments(&self) -> impl Iterator<Item = &str> + '_ {
+        self.path.split('/').filter(|s| !s.is_empty())
+    }
+
+    /**
+     * Like `segments`, but with encoded delimiters decoded, e.g. `a/b` and `c` for `/a%2Fb/c`.
+     */
+    pub fn decoded_segments(&self) -> impl Iterator<Item = String> + '_ {
+        self.segments().map(move |segment| {
+            if self.encoded_delimiters {
+                percent_decode(segment)
+            } else {
+                segment.to_string()
+            }
+        })
+    }
+
+    /**
+     * Returns a URI whose path consists of the given segments.
+     * The path stays absolute if it was absolute before or the URI has an authority.
+     * The path is rebuilt from the segments alone: it has no empty segments and no trailing
+     * '/', so `push_segment`, `pop_segment`, `with_basename` and `with_extname` turn
+     * `/a//b/` into `/a/b/...`, `/a`, `/a/...` and `/a/b...` respectively.
+     */
+    pub fn with_segments<S: AsRef<str>>(&self, segments: &[S]) -> Result<Self, UriError> {
+        let mut path = String::new();
+        if self.path.starts_with(SLASH) || !self.authority.is_empty() {
+            path.push_str(SLASH);
+        }
+        for (i, segment) in segments.iter().enumerate() {
+            let segment = segment.as_ref();
+            if segment.is_empty() || segment.contains('/') {
+                return Err(UriError::invalid_path_segment(segment));
+            }
+            if i > 0 {
+                path.push_str(SLASH);
+            }
+            path.push_str(segment);
+        }
+
+        self.with(URIChange {
+            path: Some(path),
+            ..Default::default()
+        })
+    }
+
+    /**
+     * Returns a URI with `segment` appended to the path.
+     */
+    pub fn push_segment(&self, segment: &str) -> Result<Self, UriError> {
+        let mut segments: Vec<&str> = self.segments().collect();
+        segments.push(segment);
+        self.with_segments(&segments)
+    }
+
+    /**
+     * Returns a URI with the last segment removed from the path.
+     * The original URI is returned if the path does not contain any segments.
+     */
+    pub fn pop_segment(&self) -> Result<Self, UriError> {
+        let mut segments: Vec<&str> = self.segments().collect();
+        if segments.pop().is_none() {
+            return Ok(self.clone());
+        }
+        self.with_segments(&segments)
+    }
+
+    /**
+     * Returns a URI with the last segment of the path replaced by `basename`.
+     * If the path does not contain any segments, `basename` is appended.
+     */
+    pub fn with_basename(&self, basename: &str) -> Result<Self, UriError> {
+        let mut segments: Vec<&str> = self.segments().collect();
+        segments.pop();
+        segments.push(basename);
+        self.with_segments(&segments)
+    }
+
+    /**
+     * Returns a URI with the extension of the last path segment replaced by `extname`,
+     * similar to `Path::with_extension`: only the last extension is replaced, so `foo.test.ts`
+     * becomes `foo.test.js`. A leading '.' in `extname` is optional and an empty `extname`
+     * removes the extension. The original URI is returned if the path does not contain any
+     * segments; a last segment of `.` or `..`, or one that would become `.` or `..`, fails with
+     * `UriError::InvalidPathSegment`.
+     * `Utils::with_extnames` replaces compound extensions such as `.test.ts` as a whole instead.
+     */
+    pub fn with_extname(&self, extname: &str) -> Result<Self, UriError> {
+        let basename = match self.segments().last() {
+            Some(basename) => basename,
+            None => return Ok(self.clone()),
+        };
+        if basename == "." || basename == ".." {
+            return Err(UriError::invalid_path_segment(basename));
+        }
+        // like `Path::file_stem`, `foo.` has the stem `foo` and `.gitignore` has no extension
+        let stem = match basename.rfind('.') {
+            Some(last_dot) if last_dot > 0 => &basename[..last_dot],
+            _ => basename,
+        };
+        let extname = extname.strip_prefix('.').unwrap_or(extname);
+        let basename = if extname.is_empty() {
+            stem.to_string()
+        } else {
+            format!("{}.{}", stem, extname)
+        };
+        // e.g. `..foo` without its extension
+        if basename == "." || basename == ".." {
+            return Err(UriError::invalid_path_segment(&basename));
+        }
+        self.with_basename(&basename)
+    }
+
+    /**
+     * Returns the file system path of this URI. On Windows, paths of `MAX_PATH` (260) UTF-16
+     * code units or more are returned with the extended-length `\\?\` prefix.
+     */
+    #[cfg(feature = "std")]
     pub fn fs_path(&self) -> PathBuf {
+        #[cfg(unix)]
+        if matches!(self.path_bytes, Some(PathBytes::Escaped(_))) && !is_windows() {
+            return PathBuf::from(self.fs_path_os());
+        }
         PathBuf::from(uri_to_fs_path(self, false))
     }
 
+    /**
+     * Returns the file system path of this URI like `fs_path`, as an `OsString`. The path of a
+     * file name that is not UTF-8 has the exact bytes of the name, whether the URI was created with
+     * `URI::file` or parsed, e.g. `file:///a/%FF` is the path `/a/` followed by the byte `0xFF`,
+     * where `fs_path` keeps the escape as text like vscode-uri.
+     */
+    #[cfg(all(feature = "std", unix))]
+    pub fn fs_path_os(&self) -> OsString {
+        use std::os::unix::ffi::OsStringExt;
+
+        if self.path_bytes.is_none() || is_windows() {
+            return uri_to_fs_path(self, false).into();
+        }
+        let path = self.escaped_path();
+        OsString::from_vec(unescape_path_bytes(&fs_path_text(self, &path, false)))
+    }
+
     pub fn to_string(&self, skip_encoding: bool) -> String {
-        as_formatted(self, skip_encoding)
+        as_formatted(self, skip_encoding, &EncodeOptions::default())
+    }
+
+    /**
+     * Formats this URI with the given encoding set per component.
+     * `to_string_with(&EncodeOptions::default())` is the same as `to_string(false)`.
+     */
+    pub fn to_string_with(&self, options: &EncodeOptions) -> String {
+        as_formatted(self, false, options)
     }
 }
 
@@ -622,6 +1958,82 @@ pub struct URIChange {
     pub fragment: Option<String>,
 }
 
+/**
+ * Options for `URI::parse_with_options`.
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /**
+     * Maps the forms of `file:` URIs that RFC 8089 allows onto the form `URI::file` creates:
+     * `file://localhost/etc` is `file:///etc` (see `keep_localhost`) and the legacy
+     * `file:///C|/path` is `file:///C:/path`. Off by default, like in vscode-uri, which keeps
+     * `localhost` as the authority and `C|` as is.
+     */
+    pub normalize_file_uris: bool,
+    /**
+     * With `normalize_file_uris`, keeps the authority of `file://localhost/...` instead of treating
+     * `localhost` as the local machine, i.e. as an empty authority (RFC 8089, section 2).
+     */
+    pub keep_localhost: bool,
+    /**
+     * Keeps `%2F`, `%3F`, `%23` (and `%25`, so that `%252F` stays distinct) encoded in the path,
+     * query and fragment instead of decoding them, so that e.g. `/a%2Fb/c` has the two segments
+     * `a%2Fb` and `c` and is formatted as is. Literal '/' and '?' in the query and fragment are
+     * not encoded either. The resulting URI is not equal to the URI `parse` returns.
+     */
+    pub keep_encoded_delimiters: bool,
+    /**
+     * Fails with `UriError::InvalidPercentEncoding` instead of keeping malformed escapes or
+     * escapes of invalid UTF-8 as is, see `URI::parse_checked_escapes`.
+     */
+    pub check_escapes: bool,
+}
+
+/**
+ * Options for `URI::file_with_options`.
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FileOptions {
+    /**
+     * Resolves relative paths against the current working directory of the process.
+     */
+    pub relative_to_cwd: bool,
+    /**
+     * Fails with `UriError::RelativeFilePath` if the path is relative (and not resolved against
+     * the working directory), instead of taking it for a path below the root like `file`.
+     */
+    pub strict: bool,
+}
+
+/**
+ * How `URI::parse_or_file` interpreted its input.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UriInterpretation {
+    /** A URI with a scheme, e.g. `https://host/a.ts`. */
+    Uri,
+    /** A Windows path with a drive letter, e.g. `C:\work\a.ts`. */
+    DrivePath,
+    /** A UNC path, e.g. `\\server\share\a.ts`. */
+    UncPath,
+    /** An absolute path, e.g. `/home/me/a.ts`. */
+    AbsolutePath,
+    /** A path relative to the working directory, e.g. `./a.ts`. */
+    RelativePath,
+}
+
+impl fmt::Display for UriInterpretation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UriInterpretation::Uri => write!(f, "URI"),
+            UriInterpretation::DrivePath => write!(f, "Windows path with a drive letter"),
+            UriInterpretation::UncPath => write!(f, "UNC path"),
+            UriInterpretation::AbsolutePath => write!(f, "absolute path"),
+            UriInterpretation::RelativePath => write!(f, "path relative to the working directory"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct URIComponents {
     pub scheme: String,
@@ -3,7 +3,13 @@
  * https://github.com/microsoft/vscode-uri
  */
 
+use crate::glob::{Glob, GlobOptions};
+use crate::path::Posix;
+use crate::schemas::SchemeRegistry;
 use crate::uri::{UriError, URI};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 
 pub struct Utils;
 
@@ -22,26 +28,12 @@ impl Utils {
      * @returns A URI with the joined path. All other properties of the URI (scheme, authority, query, fragments, ...) will be taken from the input URI.
      */
     pub fn join_path(uri: &URI, paths: &[&str]) -> Result<URI, UriError> {
-        let mut result = uri.path().to_string();
-        let mut had_trailing_slash = result.ends_with('/');
-
-        for path in paths {
-            if result.ends_with('/') {
-                result.push_str(path);
-            } else {
-                result.push('/');
-                result.push_str(path);
-            }
-            had_trailing_slash = path.ends_with('/');
-        }
-
-        let mut normalized = Self::normalize_path(&result);
-        if had_trailing_slash && !normalized.ends_with('/') {
-            normalized.push('/');
-        }
+        let mut all_paths = Vec::with_capacity(paths.len() + 1);
+        all_paths.push(uri.path());
+        all_paths.extend_from_slice(paths);
 
         uri.with(crate::uri::URIChange {
-            path: Some(normalized),
+            path: Some(Posix::join(&all_paths)),
             ..Default::default()
         })
     }
@@ -60,63 +52,23 @@ impl Utils {
      * @returns A URI with the resolved path. All other properties of the URI (scheme, authority, query, fragments, ...) will be taken from the input URI.
      */
     pub fn resolve_path(uri: &URI, paths: &[&str]) -> Result<URI, UriError> {
-        let mut base = uri.path().to_string();
+        let mut path = uri.path().to_string();
         let mut slash_added = false;
-        if !base.starts_with('/') {
-            base = format!("/{}", base); // make the path abstract
+        if !path.starts_with('/') {
+            path = format!("/{}", path); // make the path abstract
             slash_added = true;
         }
 
-        let mut result = base;
-        for path in paths {
-            if path.starts_with('/') {
-                result = path.to_string();
-            } else {
-                let mut segments: Vec<&str> = result.split('/').collect();
-                let path_segments: Vec<&str> = path.split('/').collect();
-
-                for segment in path_segments {
-                    if segment == "." {
-                        continue;
-                    } else if segment == ".." {
-                        if segments.len() > 1 {
-                            segments.pop();
-                        }
-                    } else {
-                        segments.push(segment);
-                    }
-                }
-
-                result = segments.join("/");
-                if result.is_empty() {
-                    result = "/".to_string();
-                }
-            }
+        let mut all_paths = Vec::with_capacity(paths.len() + 1);
+        all_paths.push(path.as_str());
+        all_paths.extend_from_slice(paths);
+        let mut resolved_path = Posix::resolve(&all_paths);
+        if slash_added && resolved_path.starts_with('/') && uri.authority().is_empty() {
+            resolved_path.remove(0);
         }
 
-        let normalized = Self::normalize_path(&result);
-
-        // Remove leading slash if it was added and there's no authority
-        let final_path = if slash_added && uri.authority().is_empty() && normalized.starts_with('/')
-        {
-            if normalized == "/" {
-                String::new()
-            } else {
-                normalized[1..].to_string()
-            }
-        } else {
-            normalized
-        };
-
-        // Always remove trailing slash except for root
-        let final_path = if final_path.len() > 1 && final_path.ends_with('/') {
-            final_path[..final_path.len() - 1].to_string()
-        } else {
-            final_path
-        };
-
         uri.with(crate::uri::URIChange {
-            path: Some(final_path),
+            path: Some(resolved_path),
             ..Default::default()
         })
     }
@@ -135,20 +87,13 @@ impl Utils {
             return Ok(uri.clone());
         }
 
-        let mut path = path.to_string();
-        // Remove trailing slashes except for root
-        while path.len() > 1 && path.ends_with('/') {
-            path.pop();
+        let mut path = Posix::dirname(path);
+        if path == "." {
+            path.clear();
         }
 
-        let new_path = match path.rfind('/') {
-            Some(0) => "/",
-            Some(i) => &path[..i],
-            None => "",
-        };
-
         uri.with(crate::uri::URIChange {
-            path: Some(new_path.to_string()),
+            path: Some(path),
             ..Default::default()
         })
     }
@@ -162,21 +107,7 @@ impl Utils {
      * @return The base name of the URIs path.
      */
     pub fn basename(uri: &URI) -> String {
-        let path = uri.path();
-        if path.is_empty() || path == "/" {
-            return String::new();
-        }
-
-        let mut path = path.to_string();
-        // Remove trailing slashes except for root
-        while path.len() > 1 && path.ends_with('/') {
-            path.pop();
-        }
-
-        match path.rfind('/') {
-            Some(last_slash) => path[last_slash + 1..].to_string(),
-            None => path,
-        }
+        Posix::basename(uri.path(), None)
     }
 
     /**
@@ -188,72 +119,211 @@ impl Utils {
      * @return The extension name of the URIs path.
      */
     pub fn extname(uri: &URI) -> String {
-        let path = uri.path();
-        if path.is_empty() || path == "/" {
-            return String::new();
-        }
+        Posix::extname(uri.path())
+    }
 
-        let mut path = path.to_string();
-        // Remove trailing slashes except for root
-        while path.len() > 1 && path.ends_with('/') {
-            path.pop();
-        }
+    /**
+     * Returns the extension of the base name of a URI's path, including the known compound
+     * extensions of `COMPOUND_EXTENSIONS`, e.g. `.d.ts` for `/src/index.d.ts` where `extname`
+     * returns `.ts`. Like `extname`, a dotfile such as `.gitignore` has no extension.
+     *
+     * @param uri The input URI.
+     * @return The extension of the base name, so that `stem(uri) + extnames(uri)` is the base name.
+     */
+    pub fn extnames(uri: &URI) -> String {
+        Self::extnames_with_options(uri, &ExtnameOptions::default())
+    }
+
+    pub fn extnames_with_options(uri: &URI, options: &ExtnameOptions) -> String {
+        split_extension(&Self::basename(uri), options).1.to_string()
+    }
+
+    /**
+     * Returns the base name of a URI's path without its extension (see `extnames`),
+     * e.g. `index` for `/src/index.d.ts` and `.gitignore` for `/.gitignore`.
+     *
+     * @param uri The input URI.
+     * @return The stem of the base name.
+     */
+    pub fn stem(uri: &URI) -> String {
+        Self::stem_with_options(uri, &ExtnameOptions::default())
+    }
+
+    pub fn stem_with_options(uri: &URI, options: &ExtnameOptions) -> String {
+        split_extension(&Self::basename(uri), options).0.to_string()
+    }
 
-        let filename = match path.rfind('/') {
-            Some(last_slash) => &path[last_slash + 1..],
-            None => &path,
+    /**
+     * Returns a URI whose base name has the extension `extname` instead of the one returned by
+     * `extnames`, e.g. `/src/foo.ts` for `/src/foo.test.ts` and `.ts`. A leading '.' is added if
+     * missing, and an empty `extname` removes the extension.
+     *
+     * Unlike `URI::with_extname`, which replaces the last extension only (`foo.test.js` for
+     * `foo.test.ts` and `.js`) and returns a URI without a base name as is, this replaces the
+     * whole extension, compound extensions included, and fails without a base name.
+     *
+     * @param uri The input URI.
+     * @param extname The new extension.
+     * @return A URI with the new base name. Fails if the URI's path has no base name, the base
+     * name is `.` or `..`, or the new base name would be `.` or `..` or contain a '/'.
+     */
+    pub fn with_extnames(uri: &URI, extname: &str) -> Result<URI, UriError> {
+        Self::with_extnames_with_options(uri, extname, &ExtnameOptions::default())
+    }
+
+    pub fn with_extnames_with_options(
+        uri: &URI,
+        extname: &str,
+        options: &ExtnameOptions,
+    ) -> Result<URI, UriError> {
+        let dot = if extname.is_empty() || extname.starts_with('.') {
+            ""
+        } else {
+            "."
         };
+        replace_basename(uri, |basename| {
+            let (stem, _) = split_extension(basename, options);
+            format!("{}{}{}", stem, dot, extname)
+        })
+    }
 
-        match filename.rfind('.') {
-            Some(last_dot) if last_dot > 0 && last_dot < filename.len() - 1 => {
-                filename[last_dot..].to_string()
-            }
-            _ => String::new(),
-        }
+    /**
+     * Returns a URI whose base name has the stem `stem` and the same extension (see `extnames`),
+     * e.g. `/src/bar.d.ts` for `/src/foo.d.ts` and `bar`.
+     *
+     * @param uri The input URI.
+     * @param stem The new stem, which must not be empty.
+     * @return A URI with the new base name. Fails if the URI's path has no base name, the base
+     * name is `.` or `..`, or `stem` is empty or contains a '/' or the new base name would be `.`
+     * or `..`.
+     */
+    pub fn with_stem(uri: &URI, stem: &str) -> Result<URI, UriError> {
+        Self::with_stem_with_options(uri, stem, &ExtnameOptions::default())
     }
 
-    pub fn normalize_path(path: &str) -> String {
-        if path.is_empty() {
-            return ".".to_string();
+    pub fn with_stem_with_options(
+        uri: &URI,
+        stem: &str,
+        options: &ExtnameOptions,
+    ) -> Result<URI, UriError> {
+        if stem.is_empty() || stem.contains('/') {
+            return Err(UriError::invalid_path_segment(stem));
         }
+        replace_basename(uri, |basename| {
+            let (_, extname) = split_extension(basename, options);
+            format!("{}{}", stem, extname)
+        })
+    }
 
-        let mut normalized = String::with_capacity(path.len());
-        let had_trailing_slash = path.ends_with('/') && path != "/";
-        let segments: Vec<&str> = path.split('/').collect();
-        let mut stack: Vec<&str> = Vec::new();
+    /**
+     * Returns whether the path of a URI matches a glob pattern (see `Glob` for the syntax).
+     * The pattern must match the whole path, so `*.{ts,js}` matches `a.ts` but not `/src/a.ts`;
+     * start it with a `**` segment to match files at any depth.
+     * Paths are compared case-insensitively if the scheme is registered with `case_insensitive_path`.
+     * Invalid patterns such as `[z-a]` silently return `false`; use `Glob::new` to report them as
+     * `UriError::InvalidGlobPattern`, or to compile a pattern once and match it repeatedly.
+     *
+     * @param uri The input URI.
+     * @param pattern The glob pattern.
+     * @return Whether the URIs path matches the pattern.
+     */
+    pub fn matches_glob(uri: &URI, pattern: &str) -> bool {
+        let options = GlobOptions {
+            ignore_case: SchemeRegistry::rules(uri.scheme()).case_insensitive_path,
+        };
+        Glob::with_options(pattern, options).is_ok_and(|glob| glob.is_match(uri))
+    }
 
-        // Handle absolute paths
-        if path.starts_with('/') {
-            normalized.push('/');
-        }
+    pub fn normalize_path(path: &str) -> String {
+        Posix::normalize(path)
+    }
 
-        for segment in segments {
-            match segment {
-                "" | "." => continue,
-                ".." => {
-                    if !stack.is_empty() && stack.last() != Some(&"..") {
-                        stack.pop();
-                    } else if !path.starts_with('/') {
-                        // For relative paths, keep the ".." segments
-                        stack.push("..");
-                    }
-                }
-                _ => stack.push(segment),
-            }
-        }
+    /**
+     * The compound extensions recognized by default by `extnames`, `stem`, `with_extnames` and
+     * `with_stem`.
+     */
+    pub const COMPOUND_EXTENSIONS: &'static [&'static str] = &[
+        ".d.ts",
+        ".d.mts",
+        ".d.cts",
+        ".d.tsx",
+        ".spec.ts",
+        ".spec.tsx",
+        ".spec.js",
+        ".spec.jsx",
+        ".test.ts",
+        ".test.tsx",
+        ".test.js",
+        ".test.jsx",
+        ".min.js",
+        ".min.css",
+        ".js.map",
+        ".tar.gz",
+        ".tar.bz2",
+        ".tar.xz",
+        ".tar.zst",
+    ];
+}
 
-        // Join the segments
-        if !stack.is_empty() {
-            normalized.push_str(&stack.join("/"));
-        } else if !normalized.starts_with('/') {
-            normalized.push('.');
-        }
+/**
+ * Options for `Utils::extnames`, `Utils::stem`, `Utils::with_extnames` and `Utils::with_stem`.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtnameOptions<'a> {
+    /** Extensions of more than one part that are kept together, matched case-insensitively. */
+    pub compound_extensions: &'a [&'a str],
+}
 
-        // Restore trailing slash if it existed
-        if had_trailing_slash {
-            normalized.push('/');
+impl Default for ExtnameOptions<'_> {
+    fn default() -> Self {
+        ExtnameOptions {
+            compound_extensions: Utils::COMPOUND_EXTENSIONS,
         }
+    }
+}
 
-        normalized
+// Splits a base name into its stem and its extension: the longest compound extension that
+// leaves a stem other than dots, or else the extension returned by `Posix::extname`.
+fn split_extension<'a>(basename: &'a str, options: &ExtnameOptions) -> (&'a str, &'a str) {
+    let compound_len = options
+        .compound_extensions
+        .iter()
+        .filter_map(|extname| {
+            let stem_len = basename.len().checked_sub(extname.len())?;
+            let suffix = basename.get(stem_len..)?;
+            let has_stem = basename[..stem_len].bytes().any(|b| b != b'.');
+            (has_stem && suffix.eq_ignore_ascii_case(extname)).then_some(extname.len())
+        })
+        .max();
+    let extname_len = compound_len.unwrap_or_else(|| Posix::extname(basename).len());
+    basename.split_at(basename.len() - extname_len)
+}
+
+// Returns a URI whose base name (which ignores trailing slashes, like `Utils::basename`) is
+// replaced with `f(basename)`. Neither may be `.` or `..`, which are not file names.
+fn replace_basename(uri: &URI, f: impl FnOnce(&str) -> String) -> Result<URI, UriError> {
+    let path = uri.path();
+    let end = path.trim_end_matches('/').len();
+    let start = path[..end].rfind('/').map_or(0, |idx| idx + 1);
+    let basename = &path[start..end];
+    if basename.is_empty() || basename == "." || basename == ".." {
+        return Err(UriError::invalid_path_segment(basename));
+    }
+    let new_basename = f(basename);
+    if new_basename.is_empty()
+        || new_basename.contains('/')
+        || new_basename == "."
+        || new_basename == ".."
+    {
+        return Err(UriError::invalid_path_segment(&new_basename));
     }
+    uri.with(crate::uri::URIChange {
+        path: Some(format!(
+            "{}{}{}",
+            &path[..start],
+            new_basename,
+            &path[end..]
+        )),
+        ..Default::default()
+    })
 }
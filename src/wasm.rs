@@ -0,0 +1,210 @@
+/*
+ * Rust implementation of vscode-uri
+ * https://github.com/microsoft/vscode-uri
+ */
+
+use crate::uri::{URIChange, URIComponents, UriError, URI};
+use crate::utils::Utils;
+use js_sys::{Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+// `MarshalledId.Uri` in VS Code
+const URI_MARSHALLED_ID: u32 = 1;
+
+fn to_js_error(err: UriError) -> JsError {
+    JsError::new(&err.to_string())
+}
+
+/**
+ * The `URI` class of the `vscode-uri` npm package.
+ */
+#[wasm_bindgen(js_name = URI)]
+pub struct WasmUri {
+    inner: URI,
+}
+
+#[wasm_bindgen(js_class = URI)]
+impl WasmUri {
+    pub fn parse(value: &str, strict: Option<bool>) -> Result<WasmUri, JsError> {
+        URI::parse_with_strict(value, strict.unwrap_or(false))
+            .map(WasmUri::from)
+            .map_err(to_js_error)
+    }
+
+    pub fn file(path: &str) -> Result<WasmUri, JsError> {
+        URI::file(path).map(WasmUri::from).map_err(to_js_error)
+    }
+
+    /**
+     * Creates a URI from an object with `scheme`, `authority`, `path`, `query` and `fragment`
+     * properties, e.g. the result of `toJSON`. Missing properties are empty.
+     */
+    #[wasm_bindgen(js_name = from)]
+    pub fn from_components(components: &JsValue) -> Result<WasmUri, JsError> {
+        let get =
+            |key: &str| Ok::<_, JsError>(string_property(components, key)?.unwrap_or_default());
+        URI::from(&URIComponents {
+            scheme: get("scheme")?,
+            authority: get("authority")?,
+            path: get("path")?,
+            query: get("query")?,
+            fragment: get("fragment")?,
+        })
+        .map(WasmUri::from)
+        .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn scheme(&self) -> String {
+        self.inner.scheme().to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn authority(&self) -> String {
+        self.inner.authority().to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn path(&self) -> String {
+        self.inner.path().to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn query(&self) -> String {
+        self.inner.query().to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn fragment(&self) -> String {
+        self.inner.fragment().to_string()
+    }
+
+    #[wasm_bindgen(getter = fsPath)]
+    pub fn fs_path(&self) -> String {
+        self.inner.fs_path().to_string_lossy().to_string()
+    }
+
+    /**
+     * Like in JavaScript, `undefined` keeps a component and `null` clears it.
+     */
+    pub fn with(&self, change: &JsValue) -> Result<WasmUri, JsError> {
+        let get = |key: &str| -> Result<Option<String>, JsError> {
+            if change.is_undefined() || change.is_null() {
+                return Ok(None);
+            }
+            let value = Reflect::get(change, &JsValue::from_str(key))
+                .map_err(|_| JsError::new("Invalid change"))?;
+            if value.is_null() {
+                return Ok(Some(String::new()));
+            }
+            string_property(change, key)
+        };
+        self.inner
+            .with(URIChange {
+                scheme: get("scheme")?,
+                authority: get("authority")?,
+                path: get("path")?,
+                query: get("query")?,
+                fragment: get("fragment")?,
+            })
+            .map(WasmUri::from)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_string_js(&self, skip_encoding: Option<bool>) -> String {
+        self.inner.to_string(skip_encoding.unwrap_or(false))
+    }
+
+    /**
+     * Returns the components like `URI.toJSON()`, leaving out empty components.
+     * Unlike in JavaScript, the cached `fsPath` and `external` values are never included.
+     */
+    #[wasm_bindgen(js_name = toJSON)]
+    pub fn to_json(&self) -> Result<JsValue, JsError> {
+        let res = Object::new();
+        let set = |key: &str, value: JsValue| {
+            Reflect::set(&res, &JsValue::from_str(key), &value)
+                .map(|_| ())
+                .map_err(|_| JsError::new("Cannot set property"))
+        };
+        set("$mid", JsValue::from(URI_MARSHALLED_ID))?;
+        for (key, value) in [
+            ("path", self.inner.path()),
+            ("scheme", self.inner.scheme()),
+            ("authority", self.inner.authority()),
+            ("query", self.inner.query()),
+            ("fragment", self.inner.fragment()),
+        ] {
+            if !value.is_empty() {
+                set(key, JsValue::from_str(value))?;
+            }
+        }
+        Ok(res.into())
+    }
+}
+
+impl From<URI> for WasmUri {
+    fn from(inner: URI) -> Self {
+        WasmUri { inner }
+    }
+}
+
+impl From<WasmUri> for URI {
+    fn from(uri: WasmUri) -> Self {
+        uri.inner
+    }
+}
+
+// Returns a string property, or `None` if it is missing. Other values are an error.
+fn string_property(object: &JsValue, key: &str) -> Result<Option<String>, JsError> {
+    let value = Reflect::get(object, &JsValue::from_str(key))
+        .map_err(|_| JsError::new(&format!("Cannot read \"{}\"", key)))?;
+    if value.is_undefined() {
+        return Ok(None);
+    }
+    value
+        .as_string()
+        .map(Some)
+        .ok_or_else(|| JsError::new(&format!("\"{}\" must be a string", key)))
+}
+
+/**
+ * The `Utils` namespace of the `vscode-uri` npm package. Since exported functions cannot be
+ * variadic, `joinPath` and `resolvePath` take the paths as an array.
+ */
+#[wasm_bindgen(js_name = Utils)]
+pub struct WasmUtils;
+
+#[wasm_bindgen(js_class = Utils)]
+impl WasmUtils {
+    #[wasm_bindgen(js_name = joinPath)]
+    pub fn join_path(uri: &WasmUri, paths: Vec<String>) -> Result<WasmUri, JsError> {
+        let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+        Utils::join_path(&uri.inner, &paths)
+            .map(WasmUri::from)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = resolvePath)]
+    pub fn resolve_path(uri: &WasmUri, paths: Vec<String>) -> Result<WasmUri, JsError> {
+        let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+        Utils::resolve_path(&uri.inner, &paths)
+            .map(WasmUri::from)
+            .map_err(to_js_error)
+    }
+
+    pub fn dirname(uri: &WasmUri) -> Result<WasmUri, JsError> {
+        Utils::dirname(&uri.inner)
+            .map(WasmUri::from)
+            .map_err(to_js_error)
+    }
+
+    pub fn basename(uri: &WasmUri) -> String {
+        Utils::basename(&uri.inner)
+    }
+
+    pub fn extname(uri: &WasmUri) -> String {
+        Utils::extname(&uri.inner)
+    }
+}
@@ -0,0 +1,146 @@
+use vscode_uri_rs::{GitUri, GitUriOptions, GitUriParams, UntitledUri, UriError, URI};
+type Result<T> = std::result::Result<T, UriError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_untitled() -> Result<()> {
+        let uri = UntitledUri::generate(1)?;
+        assert_eq!(uri.to_string(false), "untitled:Untitled-1");
+        assert_eq!(UntitledUri::index(&uri), Some(1));
+        assert!(!UntitledUri::has_associated_path(&uri));
+        assert_eq!(UntitledUri::associated_file(&uri), None);
+        assert_eq!(UntitledUri::name(&uri), "Untitled-1");
+
+        assert_eq!(
+            UntitledUri::index(&URI::parse("untitled:Untitled-12")?),
+            Some(12)
+        );
+        assert_eq!(UntitledUri::index(&URI::parse("untitled:Untitled-")?), None);
+        assert_eq!(
+            UntitledUri::index(&URI::parse("untitled:Untitled-1a")?),
+            None
+        );
+        assert_eq!(UntitledUri::index(&URI::parse("untitled:foo")?), None);
+        assert_eq!(UntitledUri::index(&URI::parse("file:///Untitled-1")?), None);
+
+        let uri = URI::parse("untitled:/Users/me/new.ts")?;
+        assert!(UntitledUri::has_associated_path(&uri));
+        assert_eq!(UntitledUri::name(&uri), "new.ts");
+        assert_eq!(
+            UntitledUri::associated_file(&uri).map(|uri| uri.to_string(false)),
+            Some("file:///Users/me/new.ts".to_string())
+        );
+        assert!(!UntitledUri::has_associated_path(&URI::parse(
+            "untitled:/"
+        )?));
+
+        let file = URI::file("/Users/me/new.ts")?;
+        let uri = UntitledUri::for_associated_resource(&file)?;
+        assert_eq!(uri.to_string(false), "untitled:/Users/me/new.ts");
+        assert_eq!(UntitledUri::associated_file(&uri), Some(file));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_generate() -> Result<()> {
+        let file = URI::file("/repo/src/a b.ts")?;
+        let uri = GitUri::generate(&file, "HEAD")?;
+        assert_eq!(uri.scheme(), "git");
+        assert_eq!(uri.path(), "/repo/src/a b.ts");
+        assert_eq!(uri.query(), r#"{"path":"/repo/src/a b.ts","ref":"HEAD"}"#);
+        assert_eq!(
+            uri.to_string(false),
+            "git:/repo/src/a%20b.ts?%7B%22path%22%3A%22%2Frepo%2Fsrc%2Fa%20b.ts%22%2C%22ref%22%3A%22HEAD%22%7D"
+        );
+        assert_eq!(URI::parse(&uri.to_string(false))?, uri);
+
+        let uri = GitUri::generate_with(
+            &file,
+            "~",
+            &GitUriOptions {
+                replace_file_extension: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(uri.path(), "/repo/src/a b.ts.git");
+        assert_eq!(uri.query(), r#"{"path":"/repo/src/a b.ts","ref":"~"}"#);
+
+        let uri = GitUri::generate_with(
+            &URI::file("/repo/lib")?,
+            "",
+            &GitUriOptions {
+                submodule_of: Some("/repo".to_string()),
+                scheme: Some("git-diff".to_string()),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(uri.scheme(), "git-diff");
+        assert_eq!(uri.path(), "/repo/lib.diff");
+        assert_eq!(
+            uri.query(),
+            r#"{"path":"/repo/lib","ref":"","submoduleOf":"/repo"}"#
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_parse() -> Result<()> {
+        let uri = URI::parse(
+            "git:/repo/a.ts?%7B%22path%22%3A%22%2Frepo%2Fa.ts%22%2C%22ref%22%3A%22HEAD%22%7D",
+        )?;
+        assert_eq!(
+            GitUri::parse(&uri),
+            Some(GitUriParams {
+                path: "/repo/a.ts".to_string(),
+                git_ref: "HEAD".to_string(),
+                submodule_of: None,
+            })
+        );
+        assert_eq!(
+            GitUri::to_file(&uri).map(|uri| uri.to_string(false)),
+            Some("file:///repo/a.ts".to_string())
+        );
+
+        fn assert_params(query: &str, expected: Option<(&str, &str, Option<&str>)>) {
+            let uri = URI::new("git", "", "/a", query, "").unwrap();
+            assert_eq!(
+                GitUri::parse(&uri),
+                expected.map(|(path, git_ref, submodule_of)| GitUriParams {
+                    path: path.to_string(),
+                    git_ref: git_ref.to_string(),
+                    submodule_of: submodule_of.map(|s| s.to_string()),
+                })
+            );
+        }
+
+        assert_params(
+            r#" { "ref" : "abc123", "path" : "c:\\work\\\"q\".ts", "extra": 1 } "#,
+            Some((r#"c:\work\"q".ts"#, "abc123", None)),
+        );
+        assert_params(
+            r#"{"path":"/\u00e4\ud83d\ude00","ref":"HEAD","submoduleOf":"/repo"}"#,
+            Some(("/ä😀", "HEAD", Some("/repo"))),
+        );
+        assert_params(r#"{"path":"/a"}"#, None);
+        assert_params(r#"{"path":"/a","ref":null}"#, None);
+        assert_params(r#"{"path":"/a","ref":"HEAD""#, None);
+        assert_params(r#"{"path":"/a","ref":"HEAD"} x"#, None);
+        assert_params("", None);
+
+        let params = GitUriParams {
+            path: "/a\"b\\c\n".to_string(),
+            git_ref: "HEAD".to_string(),
+            submodule_of: Some("/".to_string()),
+        };
+        assert_eq!(GitUriParams::from_json(&params.to_json()), Some(params));
+
+        assert_eq!(GitUri::to_file(&URI::parse("file:///a?{}")?), None);
+
+        Ok(())
+    }
+}
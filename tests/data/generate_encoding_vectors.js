@@ -0,0 +1,42 @@
+// Generates encoding_vectors.tsv: `toString()` of URIs that contain some code points in one
+// component, as formatted by vscode-uri: each ASCII code point, non-ASCII ones from the
+// Latin-1 supplement, the BMP and the astral planes, and runs that mix them. Run
+// `npm install vscode-uri` and `node generate_encoding_vectors.js`.
+'use strict';
+
+const fs = require('fs');
+const path = require('path');
+const { URI } = require('vscode-uri');
+
+// Keep in sync with `component_uri` in tests/encoding_tests.rs
+const components = {
+	userinfo: (c) => ({ scheme: 'foo', authority: `u${c}v@host`, path: '/p', query: '', fragment: '' }),
+	authority: (c) => ({ scheme: 'foo', authority: `h${c}st`, path: '/p', query: '', fragment: '' }),
+	path: (c) => ({ scheme: 'foo', authority: '', path: `/a${c}b`, query: '', fragment: '' }),
+	query: (c) => ({ scheme: 'foo', authority: '', path: '/p', query: `a${c}b`, fragment: '' }),
+	fragment: (c) => ({ scheme: 'foo', authority: '', path: '/p', query: '', fragment: `a${c}b` }),
+};
+
+// `test_vscode_uri_vectors` in tests/encoding_tests.rs checks the number of vectors
+const codePoints = [
+	...Array(128).keys(),
+	0xa0, 0xc4, 0xe9, 0x3000, 0x4e2d, 0xfeff, 0xfffd, 0x1f600, 0x10ffff,
+];
+
+// Characters `encodeURIComponentFast` leaves to `encodeURIComponent` next to ones it escapes
+// from its own table
+const sequences = [
+	...codePoints.map((code) => [code]),
+	[0x22, 0xe9], [0xe9, 0x22], [0x22, 0xe9, 0x22], [0x21, 0xe9], [0xe9, 0x21],
+	[0x27, 0xe9, 0x28], [0xe9, 0xc4], [0x3c, 0x3e, 0x4e2d, 0x20], [0x1f600, 0x23, 0x60],
+	[0x25, 0xe9, 0x7c, 0x3b],
+];
+
+let out = '';
+for (const [component, uri] of Object.entries(components)) {
+	for (const codes of sequences) {
+		const c = String.fromCodePoint(...codes);
+		out += `${component}\t${codes.join(',')}\t${URI.from(uri(c)).toString()}\n`;
+	}
+}
+fs.writeFileSync(path.join(__dirname, 'encoding_vectors.tsv'), out);
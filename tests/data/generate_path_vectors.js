@@ -0,0 +1,191 @@
+// Generates path_vectors.tsv: the results of Node's `path.posix` and `path.win32` functions for
+// the inputs of Node's test/parallel/test-path-*.js tables (and a few non-ASCII ones). Each line
+// is `flavor<TAB>function<TAB>expected<TAB>args...`, `parse` is split into one line per field, and
+// `format` is `format(parse(path))`. Results that depend on the working directory are left out.
+// Run with `node generate_path_vectors.js` (the vectors were generated with Node 20).
+'use strict';
+
+const fs = require('fs');
+const path = require('path');
+
+const paths = [
+	// join and normalize
+	'', '.', '..', './', '/', '//', '///', '\\', '\\\\', ' ', ' /foo', 'foo', 'foo/', 'foo//',
+	'./fixtures///b/../b/c.js', '/foo/../../../bar', 'a//b//../b', 'a//b//./c', 'a//b//.',
+	'/a/b/c/../../../x/y/z', '///..//./foo/.//bar', 'bar/foo../../', 'bar/foo../..',
+	'bar/foo../../baz', 'bar/foo../', 'bar/foo..', '../foo../../../bar',
+	'../.../.././.../../../bar', '../../../foo/../../../bar', '../../../foo/../../../bar/../../',
+	'../foobar/barfoo/foo/../../../bar/../../', '../.../../foobar/../../../bar/../../baz',
+	'foo/bar\\baz', '//server/share/dir/file.ext', 'C:', 'C:.', 'C:..\\abc',
+	'C:..\\..\\abc\\..\\def', 'C:\\.', 'c:/', 'c:\\', 'c:/../a', 'c:\\..\\a', 'file:stream',
+	'bar\\foo..\\..\\', 'bar\\foo..\\..', 'bar\\foo..\\..\\baz', 'bar\\foo..\\', 'bar\\foo..',
+	'..\\foo..\\..\\..\\bar', '..\\...\\..\\.\\...\\..\\..\\bar', '\\\\?\\D:/XY\\Z',
+	'\\\\.\\foo', '\\\\.\\foo\\', '\\\\foo\\bar', '\\\\foo\\bar\\', '\\\\foo\\bar\\baz',
+	'\\\\?\\PHYSICALDRIVE0', '\\\\.\\PHYSICALDRIVE0\\', '//foo', '//foo/', '///foo/bar',
+	'\\\\\\/foo/bar', '//server//share', '//server/share', '//server/share/',
+	// reserved names and colons (CVE-2024-36139, CVE-2025-27210)
+	'test/../C:/Windows', 'test/../C:\\Windows', './upload/../C:/Windows', 'test/../c/Windows',
+	'C:/Windows', 'C:\\..\\path', '\\\\?\\C:/test', 'test/../\\\\?\\C:\\test', 'test/../C:',
+	'test/../C:\\test', 'test\\..\\C:/test', 'test\\..\\C:', 'test\\..\\C:file', 'test/..\\C:',
+	'\\\\.\\CON', 'CON', 'CON:', 'CON:.\\', 'con:', 'COM1:', 'COM1:.\\..\\..\\foo', 'LPT9:',
+	'NUL:', 'COM\u00b9:', 'LPT\u00b3:..\\x', 'C:\\CON', 'c:\\CON:.\\..\\..\\foo', ':foo', 'a:b:c',
+	'a:', 'a:b', 'ab:', 'ab:\\c', 'CONx', 'AUXX', 'NUL.txt',
+	// dirname, basename, extname and parse
+	'/a/b/', '/a/b', '/a', '/a/', 'a', 'a/', '//a', 'foo/bar/', '/foo/bar//', 'c:\\foo',
+	'c:\\foo\\', 'c:\\foo\\bar', 'c:\\foo\\bar\\', 'c:\\foo\\bar\\baz', 'c:\\foo bar\\baz', 'c:',
+	'c:foo', 'c:foo\\', 'c:foo\\bar', 'c:foo\\bar\\', 'c:foo\\bar\\baz', 'file:', 'file:stream',
+	'\\\\unc\\share', '\\\\unc\\share\\foo', '\\\\unc\\share\\foo\\', '\\\\unc\\share\\foo\\bar',
+	'\\\\unc\\share\\foo\\bar\\', '\\\\unc\\share\\foo\\bar\\baz', '/a/b/c/d', '/basename.ext',
+	'basename.ext', 'basename.ext/', 'basename.ext//', 'aaa/bbb', 'aaa/bbb/', 'aaa/bbb//',
+	'/aaa/bbb', '/aaa/', '/aaa/b', 'a/b/', 'file', 'file.js', 'file/', '/file.js',
+	'path/file.js', '/path/file.js', 'file.', '.file', '.file.', '.file.ext', 'file.ext.ext',
+	'.path/file.ext', 'path/.file.ext', '..file..', '...', '...ext', '....', 'file.ext/',
+	'file.ext//', 'file/', 'file//', 'file./', 'file.//', '.file/', '.file.ext/', '..', '../',
+	'./', '/.', '/..', '/...', '.\\', '..\\', 'file.ext\\', 'file.ext\\\\', 'file\\',
+	'file\\\\', 'file.\\', 'file.\\\\', '\\\\.file\\\\', '.\\foo\\..\\bar', 'C:\\foo\\..',
+	'C:\\foo\\..\\', 'C:abc', 'C:\\abc', 'C:\\abc\\', 'C:\\abc.d\\', '\\\\server\\share\\file',
+	'\\\\server two\\shared folder\\file path.zip', '\\\\teela\\admin$\\system32',
+	'\\\\?\\UNC\\server\\share', 'a.b.c', 'a.d.ts', 'index.test.tsx', '\u00e4/\u00f6.x',
+	'C:\\\u00c4\\\u00d6.Ts', '\u00c4:\\x', '/\u0130/a', 'c:\\\u0130\\a',
+];
+
+const joinArgs = [
+	['.', 'x/b', '..', '/b/c.js'], [], ['/.', 'x/b', '..', '/b/c.js'], ['/foo', '../../../bar'],
+	['foo', '../../../bar'], ['foo/', '../../../bar'], ['foo/x', '../../../bar'],
+	['foo/x', './bar'], ['foo/x/', './bar'], ['foo/x/', '.', 'bar'], ['./'], ['.', './'],
+	['.', '.', '.'], ['.', './', '.'], ['.', '/./', '.'], ['.', '/////./', '.'], ['.'],
+	['', '.'], ['', 'foo'], ['foo', '/bar'], ['', '/foo'], ['', '', '/foo'], ['', '', 'foo'],
+	['foo', ''], ['foo/', ''], ['foo', '', '/bar'], ['./', '..', '/foo'],
+	['./', '..', '..', '/foo'], ['.', '..', '..', '/foo'], ['', '..', '..', '/foo'], ['/'],
+	['/', '.'], ['/', '..'], ['/', '..', '..'], [''], ['', ''], [' /foo'], [' ', 'foo'],
+	[' ', '.'], [' ', '/'], [' ', ''], ['/', 'foo'], ['/', '/foo'], ['/', '//foo'],
+	['/', '', '/foo'], ['', '/', 'foo'], ['', '/', '/foo'],
+	['//foo/bar'], ['\\/foo/bar'], ['\\\\foo/bar'], ['//foo', 'bar'], ['//foo/', 'bar'],
+	['//foo', '/bar'], ['//foo', '', 'bar'], ['//foo/', '', 'bar'], ['//foo/', '', '/bar'],
+	['', '//foo', 'bar'], ['', '//foo/', 'bar'], ['', '//foo/', '/bar'], ['\\', 'foo/bar'],
+	['\\', '/foo/bar'], ['', '/', '/foo/bar'], ['//', 'foo/bar'], ['//', '/foo/bar'],
+	['\\\\', '/', '/foo/bar'], ['//'], ['//foo'], ['//foo/'], ['//foo', '/'],
+	['//foo', '', '/'], ['///foo/bar'], ['\\\\\\/foo/bar'], ['\\\\foo/bar'], ['c:'], ['c:.'],
+	['c:', ''], ['', 'c:'], ['c:.', '/'], ['c:.', 'file'], ['c:', '/'], ['c:', 'file'],
+	['\\\\.\\foo'], ['\\\\.\\foo\\'], ['C:', '..\\foo'], ['test/..', 'C:'], ['.', 'C:\\x'],
+	['a', 'CON:'], ['CON', ':x'], ['\u00e4', '..', '\u00f6'],
+];
+
+const resolveArgs = [
+	['/var/lib', '../', 'file/'], ['/var/lib', '/../', 'file/'], ['a/b/c/', '../../..'], ['.'],
+	['/some/dir', '.', '/absolute/'], ['/foo/tmp.3/', '../tmp.3/cycles/root.js'],
+	['c:/blah\\blah', 'd:/games', 'c:../a'], ['c:/ignore', 'd:\\a/b\\c/d', '\\e.exe'],
+	['c:/ignore', 'c:/some/file'], ['d:/ignore', 'd:some/dir//'], ['//server/share', '..', 'relative\\'],
+	['c:/', '//'], ['c:/', '//dir'], ['c:/', '//server/share'], ['c:/', '//server//share'],
+	['c:/', '///some//dir'], ['C:\\foo\\tmp.3\\', '..\\tmp.3\\cycles\\root.js'],
+	['\\\\.\\PHYSICALDRIVE0'], ['\\\\?\\PHYSICALDRIVE0'], ['\\\\.\\C:\\a', '..\\b'],
+	['C:\\a', 'CON:', 'b'], ['/', 'C:'], ['D:\\', '\u00c4\\..\\\u00d6'], [],
+];
+
+const relativeArgs = [
+	['c:/blah\\blah', 'd:/games'], ['c:/aaaa/bbbb', 'c:/aaaa'], ['c:/aaaa/bbbb', 'c:/cccc'],
+	['c:/aaaa/bbbb', 'c:/aaaa/bbbb'], ['c:/aaaa/bbbb', 'c:/aaaa/cccc'], ['c:/aaaa/', 'c:/aaaa/cccc'],
+	['c:/', 'c:\\aaaa\\bbbb'], ['c:/aaaa/bbbb', 'd:\\'], ['c:/AaAa/bbbb', 'c:/aaaa/bbbb'],
+	['c:/aaaaa/', 'c:/aaaa/cccc'], ['C:\\foo\\bar\\baz\\quux', 'C:\\'],
+	['C:\\foo\\test', 'C:\\foo\\test\\bar\\package.json'], ['C:\\foo\\bar\\baz-quux', 'C:\\foo\\bar\\baz'],
+	['C:\\foo\\bar\\baz', 'C:\\foo\\bar\\baz-quux'], ['\\\\foo\\bar', '\\\\foo\\bar\\baz'],
+	['\\\\foo\\bar\\baz', '\\\\foo\\bar'], ['\\\\foo\\bar\\baz-quux', '\\\\foo\\bar\\baz'],
+	['\\\\foo\\bar\\baz', '\\\\foo\\bar\\baz-quux'], ['C:\\baz-quux', 'C:\\baz'],
+	['C:\\baz', 'C:\\baz-quux'], ['\\\\foo\\baz-quux', '\\\\foo\\baz'], ['\\\\foo\\baz', '\\\\foo\\baz-quux'],
+	['C:\\baz', '\\\\foo\\bar\\baz'], ['\\\\foo\\bar\\baz', 'C:\\baz'], ['C:\\', 'C:\\foo'],
+	['C:\\foo', 'C:\\'], ['c:\\\u0130\\a', 'c:\\\u0130\\b'], ['c:\\a\\\u0130', 'c:\\a'],
+	['c:\\a', 'c:\\a\\\u0130\\b'], ['c:\\\u0130', 'd:\\x'], ['c:\\\u212a\\a', 'c:\\k\\b'],
+	['c:\\\u00c4\\a', 'c:\\\u00e4\\b'], ['/var/lib', '/var'], ['/var/lib', '/bin'], ['/var/lib', '/var/lib'],
+	['/var/lib', '/var/apache'], ['/var/', '/var/lib'], ['/', '/var/lib'],
+	['/foo/test', '/foo/test/bar/package.json'], ['/Users/a/web/b/test/mails', '/Users/a/web/b'],
+	['/foo/bar/baz-quux', '/foo/bar/baz'], ['/foo/bar/baz', '/foo/bar/baz-quux'], ['/baz-quux', '/baz'],
+	['/baz', '/baz-quux'], ['/page1/page2/foo', '/'], ['/\u00e4/a', '/\u00e4/b'], ['a', 'a'],
+];
+
+const basenameArgs = [
+	['/aaa/bbb', '/bbb'], ['/aaa/bbb', 'a/bbb'], ['/aaa/bbb', 'bbb'], ['/aaa/bbb//', 'bbb'],
+	['/aaa/bbb', 'bb'], ['/aaa/bbb', 'b'], ['/aaa/bbb', ''], ['/aaa/', 'aaa'], ['/aaa/b', 'b'],
+	['/a/b', 'b'], ['//a', 'a'], ['a', 'a'], ['file.js', '.js'], ['/dir/basename.ext', '.ext'],
+	['/basename.ext', '.ext'], ['basename.ext', '.ext'], ['basename.ext/', '.ext'],
+	['basename.ext//', '.ext'], ['aaa/bbb', 'bbb'], ['aaa/bbb', 'a/bbb'], ['aaa/bbb//', 'bbb'],
+	['aaa/bbb', 'bb'], ['aaa/bbb', 'b'], ['js', '.js'], ['a/js', '.js'], ['index.d.ts', '.ts'],
+	['index.d.ts', '.d.ts'], ['\\dir\\basename.ext', '.ext'], ['\\basename.ext', '.ext'],
+	['basename.ext\\', '.ext'], ['basename.ext\\\\', '.ext'], ['foo', 'foo'], ['aaa\\bbb', 'bbb'],
+	['aaa\\bbb', 'a\\bbb'], ['aaa\\bbb\\\\\\\\', 'bbb'], ['aaa\\bbb', 'bb'], ['aaa\\bbb', 'b'],
+	['C:\\foo.html', '.html'], ['C:foo.html', '.html'], ['C:\\foo.HTML', '.html'],
+	['\u00e4\u00f6.\u00fc', '.\u00fc'], ['\u00e4\u00f6.\u00fc', '\u00f6.\u00fc'],
+];
+
+const flavors = { posix: path.posix, win32: path.win32 };
+const fields = ['root', 'dir', 'base', 'ext', 'name'];
+
+function line(flavor, fn, expected, args) {
+	const values = [flavor, fn, String(expected), ...args];
+	for (const value of values) {
+		if (/[\t\n]/.test(value)) {
+			throw new Error(`cannot write ${JSON.stringify(value)}`);
+		}
+	}
+	return values.join('\t') + '\n';
+}
+
+// Returns the result of `fn`, or `undefined` if it depends on the working directory.
+function independentOfCwd(fn) {
+	const cwd = process.cwd();
+	try {
+		process.chdir('/');
+		const a = fn();
+		process.chdir(__dirname);
+		const b = fn();
+		return a === b ? a : undefined;
+	} finally {
+		process.chdir(cwd);
+	}
+}
+
+let out = '';
+let skipped = 0;
+for (const [flavor, p] of Object.entries(flavors)) {
+	for (const args of joinArgs) {
+		out += line(flavor, 'join', p.join(...args), args);
+	}
+	for (const args of resolveArgs) {
+		const result = independentOfCwd(() => p.resolve(...args));
+		if (result === undefined) {
+			skipped++;
+		} else {
+			out += line(flavor, 'resolve', result, args);
+		}
+	}
+	for (const args of relativeArgs) {
+		const result = independentOfCwd(() => p.relative(...args));
+		if (result === undefined) {
+			skipped++;
+		} else {
+			out += line(flavor, 'relative', result, args);
+		}
+	}
+	for (const args of basenameArgs) {
+		out += line(flavor, 'basename', p.basename(...args), args);
+	}
+	for (const value of paths) {
+		out += line(flavor, 'normalize', p.normalize(value), [value]);
+		out += line(flavor, 'isAbsolute', p.isAbsolute(value), [value]);
+		out += line(flavor, 'dirname', p.dirname(value), [value]);
+		out += line(flavor, 'basename', p.basename(value), [value]);
+		out += line(flavor, 'extname', p.extname(value), [value]);
+		const parsed = p.parse(value);
+		for (const field of fields) {
+			out += line(flavor, `parse.${field}`, parsed[field], [value]);
+		}
+		out += line(flavor, 'format', p.format(parsed), [value]);
+		const namespaced = independentOfCwd(() => p.toNamespacedPath(value));
+		if (namespaced === undefined) {
+			skipped++;
+		} else {
+			out += line(flavor, 'toNamespacedPath', namespaced, [value]);
+		}
+	}
+}
+fs.writeFileSync(path.join(__dirname, 'path_vectors.tsv'), out);
+console.log(`${out.split('\n').length - 1} vectors, ${skipped} skipped`);
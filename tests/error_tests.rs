@@ -0,0 +1,102 @@
+use vscode_uri_rs::{ErrorContext, URIChange, UriComponent, UriError, URI};
+type Result<T> = std::result::Result<T, UriError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(err: &UriError) -> (&str, std::ops::Range<usize>, UriComponent) {
+        (err.input(), err.span(), err.component())
+    }
+
+    #[test]
+    fn test_new_errors() {
+        let err = URI::new("a b", "", "/x", "", "").unwrap_err();
+        assert_eq!(err.to_string(), "Scheme contains illegal characters");
+        assert_eq!(context(&err), ("a b", 1..2, UriComponent::Scheme));
+        assert_eq!(
+            err.context(),
+            &ErrorContext {
+                input: "a b".to_string(),
+                span: 1..2,
+                component: UriComponent::Scheme,
+            }
+        );
+
+        let err = URI::new("foo", "host", "bär", "", "").unwrap_err();
+        assert_eq!(context(&err), ("bär", 0..1, UriComponent::Path));
+        let err = URI::new("foo", "", "//x", "", "").unwrap_err();
+        assert_eq!(context(&err), ("//x", 0..2, UriComponent::Path));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        // the span is in the parsed value, also when the component was percent-encoded
+        let err = URI::parse("foo:%2F%2Fa").unwrap_err();
+        assert!(matches!(err, UriError::InvalidPathWithoutAuthority { .. }));
+        assert_eq!(context(&err), ("foo:%2F%2Fa", 4..10, UriComponent::Path));
+
+        let err = URI::parse_checked_escapes("foo:/%C3%BC%zz").unwrap_err();
+        assert_eq!(
+            context(&err),
+            ("foo:/%C3%BC%zz", 11..14, UriComponent::Path)
+        );
+    }
+
+    #[test]
+    fn test_with_errors() -> Result<()> {
+        // the error is about the changed component, not the one that is checked
+        let err = URI::parse("foo:bar")?
+            .with(URIChange {
+                authority: Some("host".to_string()),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert!(matches!(err, UriError::InvalidAuthorityPath { .. }));
+        assert_eq!(context(&err), ("host", 0..4, UriComponent::Authority));
+
+        let err = URI::parse("foo://host/a")?
+            .with(URIChange {
+                path: Some("b".to_string()),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert_eq!(context(&err), ("b", 0..1, UriComponent::Path));
+
+        let err = URI::parse("foo:/a")?
+            .with(URIChange {
+                scheme: Some("f@o".to_string()),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert_eq!(context(&err), ("f@o", 1..2, UriComponent::Scheme));
+
+        let err = URI::parse("foo:/a")?.push_segment("x/y").unwrap_err();
+        assert_eq!(context(&err), ("x/y", 1..2, UriComponent::Path));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "miette")]
+    fn test_miette_diagnostic() {
+        use miette::{Diagnostic, NarratableReportHandler};
+
+        let err = URI::parse("a b:x").unwrap_err();
+        assert_eq!(
+            err.code().unwrap().to_string(),
+            "vscode_uri::illegal_scheme_characters"
+        );
+        let labels: Vec<_> = err.labels().unwrap().collect();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].label(), Some("illegal character in the scheme"));
+        assert_eq!((labels[0].offset(), labels[0].len()), (1, 1));
+
+        let mut report = String::new();
+        NarratableReportHandler::new()
+            .render_report(&mut report, &err)
+            .unwrap();
+        assert!(report.contains("Scheme contains illegal characters"));
+        assert!(report.contains("a b:x"));
+        assert!(report.contains("illegal character in the scheme"));
+    }
+}
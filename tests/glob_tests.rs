@@ -0,0 +1,340 @@
+use vscode_uri_rs::glob::{Glob, GlobOptions, RelativePattern};
+use vscode_uri_rs::{
+    ErrorContext, SchemeRegistry, SchemeRules, UriComponent, UriError, Utils, URI,
+};
+type Result<T> = std::result::Result<T, UriError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_glob_match(pattern: &str, input: &str) {
+        assert!(
+            Glob::new(pattern).unwrap().is_match_path(input),
+            "{} should match {}",
+            pattern,
+            input
+        );
+    }
+
+    fn assert_no_glob_match(pattern: &str, input: &str) {
+        assert!(
+            !Glob::new(pattern).unwrap().is_match_path(input),
+            "{} should not match {}",
+            pattern,
+            input
+        );
+    }
+
+    #[test]
+    fn test_simple() {
+        assert_glob_match("node_modules", "node_modules");
+        assert_no_glob_match("node_modules", "node_module");
+        assert_no_glob_match("node_modules", "/node_modules");
+        assert_no_glob_match("node_modules", "test/node_modules");
+
+        assert_glob_match("test.txt", "test.txt");
+        assert_no_glob_match("test.txt", "test?txt");
+        assert_no_glob_match("test.txt", "/text.txt");
+        assert_no_glob_match("test.txt", "test/test.txt");
+
+        assert_glob_match("test(.txt", "test(.txt");
+        assert_glob_match("qunit", "qunit");
+        assert_no_glob_match("qunit", "qunit.css");
+        assert_no_glob_match("qunit", "test/qunit");
+
+        assert_glob_match("/DNXConsoleApp/**/*.cs", "/DNXConsoleApp/Program.cs");
+        assert_glob_match("/DNXConsoleApp/**/*.cs", "/DNXConsoleApp/foo/Program.cs");
+
+        assert_no_glob_match("", "");
+        assert_no_glob_match("", "foo");
+    }
+
+    #[test]
+    fn test_dot_hidden() {
+        assert_glob_match(".*", ".git");
+        assert_glob_match(".*", ".hidden.txt");
+        assert_no_glob_match(".*", "git");
+        assert_no_glob_match(".*", "hidden.txt");
+        assert_no_glob_match(".*", "path/.git");
+
+        assert_glob_match("**/.*", ".git");
+        assert_glob_match("**/.*", "/.git");
+        assert_glob_match("**/.*", "path/.git");
+        assert_glob_match("**/.*", "/path/.hidden.txt");
+        assert_no_glob_match("**/.*", "/path/hidden.txt");
+
+        assert_glob_match("**/.*/**", ".git/foo");
+        assert_glob_match("**/.*/**", "path/.git/foo");
+        assert_no_glob_match("**/.*/**", "path/git/foo");
+    }
+
+    #[test]
+    fn test_file_pattern() {
+        assert_glob_match("*.js", "foo.js");
+        assert_no_glob_match("*.js", "folder/foo.js");
+        assert_no_glob_match("*.js", "/node_modules/foo.js");
+        assert_no_glob_match("*.js", "foo.jss");
+        assert_no_glob_match("*.js", "some.js/test");
+
+        assert_glob_match("html.*", "html.js");
+        assert_glob_match("html.*", "html.txt");
+        assert_no_glob_match("html.*", "htm.txt");
+
+        assert_glob_match("*.*", "html.js");
+        assert_no_glob_match("*.*", "/node_modules/foo.js");
+
+        assert_glob_match("node_modules/test/*.js", "node_modules/test/foo.js");
+        assert_no_glob_match("node_modules/test/*.js", "folder/foo.js");
+        assert_no_glob_match("node_modules/test/*.js", "node_modules/test/foo/bar.js");
+    }
+
+    #[test]
+    fn test_star() {
+        assert_glob_match("node*modules", "node_modules");
+        assert_glob_match("node*modules", "node_super_modules");
+        assert_no_glob_match("node*modules", "node_module");
+        assert_no_glob_match("node*modules", "/node_modules");
+        assert_no_glob_match("node*modules", "test/node_modules");
+
+        assert_glob_match("*", "html.js");
+        assert_glob_match("*", "html");
+        assert_glob_match("*", "");
+        assert_no_glob_match("*", "foo/bar");
+    }
+
+    #[test]
+    fn test_file_and_folder_match() {
+        assert_glob_match("**/node_modules", "/node_modules");
+        assert_glob_match("**/node_modules", "node_modules");
+        assert_glob_match("**/node_modules", "/a/node_modules");
+        assert_no_glob_match("**/node_modules", "/a/node_modules/foo");
+        assert_glob_match("**/node_modules/**", "/a/node_modules/foo");
+        assert_glob_match("**/node_modules/**", "node_modules");
+    }
+
+    #[test]
+    fn test_questionmark() {
+        assert_glob_match("node?modules", "node_modules");
+        assert_no_glob_match("node?modules", "node_super_modules");
+        assert_no_glob_match("node?modules", "node/modules");
+
+        assert_glob_match("?", "h");
+        assert_no_glob_match("?", "html.txt");
+        assert_no_glob_match("?", "/a/b/c/html.txt");
+    }
+
+    #[test]
+    fn test_globstar() {
+        assert_glob_match("**/*.js", "foo.js");
+        assert_glob_match("**/*.js", "/foo.js");
+        assert_glob_match("**/*.js", "folder/foo.js");
+        assert_glob_match("**/*.js", "/node_modules/foo.js");
+        assert_no_glob_match("**/*.js", "foo.jss");
+        assert_no_glob_match("**/*.js", "some.js/test");
+        assert_no_glob_match("**/*.js", "/some.js/test");
+
+        assert_glob_match("**/project.json", "project.json");
+        assert_glob_match("**/project.json", "/project.json");
+        assert_glob_match("**/project.json", "some/folder/project.json");
+        assert_no_glob_match("**/project.json", "some/folder/file_project.json");
+        assert_no_glob_match("**/project.json", "some/folder/fileproject.json");
+        assert_no_glob_match("**/project.json", "some/rrproject.json");
+        assert_glob_match("**/project.json", "some\\rrproject.json\\project.json");
+
+        assert_glob_match("test/**", "test");
+        assert_glob_match("test/**", "test/foo");
+        assert_glob_match("test/**", "test/foo/");
+        assert_glob_match("test/**", "test/foo.js");
+        assert_glob_match("test/**", "test/other/foo.js");
+        assert_no_glob_match("test/**", "est/other/foo.js");
+
+        assert_glob_match("**", "/");
+        assert_glob_match("**", "foo.js");
+        assert_glob_match("**", "folder/foo.js");
+        assert_glob_match("**", "folder/foo/");
+
+        assert_glob_match("**/**/*.js", "foo.js");
+        assert_glob_match("**/**/*.js", "folder/foo.js");
+
+        assert_glob_match("**/node_modules/**/*.js", "node_modules/foo.js");
+        assert_glob_match("**/node_modules/**/*.js", "folder/node_modules/foo.js");
+        assert_no_glob_match("**/node_modules/**/*.js", "node_modules/foo.ts");
+        assert_no_glob_match("**/node_modules/**/*.js", "foo/node_modules/foo.ts");
+
+        assert_glob_match(
+            "{**/node_modules/**,**/.git/**,**/bower_components/**}",
+            "node_modules",
+        );
+        assert_glob_match(
+            "{**/node_modules/**,**/.git/**,**/bower_components/**}",
+            "/node_modules/more",
+        );
+        assert_glob_match(
+            "{**/node_modules/**,**/.git/**,**/bower_components/**}",
+            "some/test/node_modules",
+        );
+        assert_glob_match(
+            "{**/node_modules/**,**/.git/**,**/bower_components/**}",
+            "C:\\\\some\\test\\bower_components\\more",
+        );
+        assert_no_glob_match(
+            "{**/node_modules/**,**/.git/**,**/bower_components/**}",
+            "/some/test/.gitignore",
+        );
+    }
+
+    #[test]
+    fn test_brace_expansion() {
+        assert_glob_match("*.{html,js}", "foo.js");
+        assert_glob_match("*.{html,js}", "foo.html");
+        assert_no_glob_match("*.{html,js}", "folder/foo.js");
+        assert_no_glob_match("*.{html,js}", "foo.css");
+
+        assert_glob_match("**/*.{html,js}", "foo.js");
+        assert_glob_match("**/*.{html,js}", "/foo.js");
+        assert_glob_match("**/*.{html,js}", "folder/foo.html");
+
+        assert_glob_match("{foo,bar}", "foo");
+        assert_glob_match("{foo,bar}", "bar");
+        assert_no_glob_match("{foo,bar}", "foo/bar");
+
+        assert_glob_match("{**/*.d.ts,**/*.js}", "/foo.js");
+        assert_glob_match("{**/*.d.ts,**/*.js}", "testing/foo.d.ts");
+        assert_no_glob_match("{**/*.d.ts,**/*.js}", "/foo.ts");
+
+        assert_glob_match("{**/*.d.ts,**/*.js,foo.[0-9]}", "foo.5");
+        assert_no_glob_match("{**/*.d.ts,**/*.js,foo.[0-9]}", "foo.f");
+
+        assert_glob_match("prefix/{**/*.d.ts,**/*.js,foo.[0-9]}", "prefix/foo.5");
+        assert_glob_match("prefix/{**/*.d.ts,**/*.js,foo.[0-9]}", "prefix/foo.js");
+        assert_no_glob_match("prefix/{**/*.d.ts,**/*.js,foo.[0-9]}", "prefix/foo.f");
+    }
+
+    #[test]
+    fn test_brackets() {
+        assert_glob_match("foo.[0-9]", "foo.5");
+        assert_glob_match("foo.[0-9]", "foo.0");
+        assert_no_glob_match("foo.[0-9]", "foo.f");
+
+        assert_glob_match("foo.[^0-9]", "foo.f");
+        assert_glob_match("foo.[!0-9]", "foo.f");
+        assert_no_glob_match("foo.[!0-9]", "foo.5");
+
+        assert_glob_match("foo.[]]", "foo.]");
+        assert_glob_match("foo.[]-]", "foo.-");
+        assert_glob_match("foo.[*]", "foo.*");
+        assert_no_glob_match("foo.[*]", "foo.a");
+        assert_no_glob_match("foo.[/]", "foo./");
+    }
+
+    #[test]
+    fn test_ignore_case() -> Result<()> {
+        let glob = Glob::new("**/*.JS")?;
+        assert!(!glob.is_match_path("/foo.js"));
+        let glob = Glob::with_options("**/*.JS", GlobOptions { ignore_case: true })?;
+        assert!(glob.is_match_path("/foo.js"));
+        assert!(glob.is_match_path("/FOO.Js"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_relative_pattern() -> Result<()> {
+        let base = URI::parse("file:///workspace/project")?;
+        let glob = Glob::relative(&RelativePattern::new(base.clone(), "**/*.ts"))?;
+        assert_eq!(glob.base(), Some(&base));
+
+        assert!(glob.is_match(&URI::parse("file:///workspace/project/a.ts")?));
+        assert!(glob.is_match(&URI::parse("file:///workspace/project/src/a.ts")?));
+        assert!(!glob.is_match(&URI::parse("file:///workspace/project/a.js")?));
+        assert!(!glob.is_match(&URI::parse("file:///workspace/other/a.ts")?));
+        assert!(!glob.is_match(&URI::parse("file:///workspace/project2/a.ts")?));
+        assert!(!glob.is_match(&URI::parse("file:///workspace/Project/a.ts")?));
+        assert!(!glob.is_match(&URI::parse("vscode-vfs:///workspace/project/a.ts")?));
+        assert!(!glob.is_match(&URI::parse("file://server/workspace/project/a.ts")?));
+
+        let glob = Glob::relative(&RelativePattern::new(
+            URI::parse("file:///workspace/project/")?,
+            "src/*.ts",
+        ))?;
+        assert!(glob.is_match(&URI::parse("file:///workspace/project/src/a.ts")?));
+        assert!(!glob.is_match(&URI::parse("file:///workspace/project/a.ts")?));
+
+        let glob = Glob::relative_with_options(
+            &RelativePattern::new(URI::parse("file:///c%3A/Work")?, "*.TS"),
+            GlobOptions { ignore_case: true },
+        )?;
+        assert!(glob.is_match(&URI::parse("file:///c%3A/work/a.ts")?));
+        assert!(glob.is_match_path("/c:/WORK/a.ts"));
+        assert!(!glob.is_match_path("/c:/WORK/b/a.ts"));
+
+        let glob = Glob::relative(&RelativePattern::new(
+            URI::parse("file:///workspace")?,
+            "**",
+        ))?;
+        assert!(glob.is_match(&URI::parse("file:///workspace")?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        assert_eq!(
+            Glob::new("foo.[z-a]").unwrap_err(),
+            UriError::InvalidGlobPattern {
+                context: Box::new(ErrorContext {
+                    input: "foo.[z-a]".to_string(),
+                    span: 0..9,
+                    component: UriComponent::Path,
+                }),
+            }
+        );
+        assert!(!Utils::matches_glob(
+            &URI::parse("file:///foo.a").unwrap(),
+            "foo.[z-a]"
+        ));
+    }
+
+    #[test]
+    fn test_matches_whole_path() -> Result<()> {
+        let uri = URI::parse("file:///src/b.ts")?;
+        assert!(Utils::matches_glob(&uri, "/src/*.{ts,js}"));
+        assert!(Utils::matches_glob(&uri, "**/*.{ts,js}"));
+        assert!(!Utils::matches_glob(&uri, "*.{ts,js}"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_utils_matches_glob() -> Result<()> {
+        assert!(Utils::matches_glob(
+            &URI::parse("file:///a/b/node_modules/c.js")?,
+            "{**/node_modules/**,**/.git/**}"
+        ));
+        assert!(Utils::matches_glob(
+            &URI::parse("https://example.com/a/index.html?q")?,
+            "**/*.{htm,html}"
+        ));
+        assert!(!Utils::matches_glob(
+            &URI::parse("file:///a/b/c.js")?,
+            "{**/node_modules/**,**/.git/**}"
+        ));
+
+        assert!(!Utils::matches_glob(
+            &URI::parse("test-glob-ci:/A/B.TXT")?,
+            "/a/*.txt"
+        ));
+        let rules = SchemeRules {
+            case_insensitive_path: true,
+            ..Default::default()
+        };
+        SchemeRegistry::with_rules("test-glob-ci", rules, || {
+            assert!(Utils::matches_glob(
+                &URI::parse("test-glob-ci:/A/B.TXT")?,
+                "/a/*.txt"
+            ));
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use vscode_uri_rs::{CellUri, UriError, URI};
+type Result<T> = std::result::Result<T, UriError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() -> Result<()> {
+        fn assert_generate(notebook: &str, handle: u32, expected: &str) -> Result<()> {
+            let cell = CellUri::generate(&URI::parse(notebook)?, handle)?;
+            assert_eq!(cell.to_string(false), expected);
+            Ok(())
+        }
+
+        // Taken from cell URIs sent by VS Code
+        assert_generate(
+            "file:///Users/me/nb.ipynb",
+            0,
+            "vscode-notebook-cell:/Users/me/nb.ipynb#W0sZmlsZQ%3D%3D",
+        )?;
+        assert_generate(
+            "file:///Users/me/nb.ipynb",
+            6,
+            "vscode-notebook-cell:/Users/me/nb.ipynb#W6sZmlsZQ%3D%3D",
+        )?;
+        assert_generate(
+            "file:///Users/me/nb.ipynb",
+            7,
+            "vscode-notebook-cell:/Users/me/nb.ipynb#X10sZmlsZQ%3D%3D",
+        )?;
+        assert_generate(
+            "file:///c%3A/work/nb.ipynb",
+            49,
+            "vscode-notebook-cell:/c%3A/work/nb.ipynb#Y100sZmlsZQ%3D%3D",
+        )?;
+        assert_generate(
+            "untitled:Untitled-1.ipynb",
+            2,
+            "vscode-notebook-cell:Untitled-1.ipynb#W2sdW50aXRsZWQ%3D",
+        )?;
+        assert_generate(
+            "vscode-remote://wsl%2Bubuntu/home/me/nb.ipynb",
+            100,
+            "vscode-notebook-cell://wsl%2Bubuntu/home/me/nb.ipynb#Y202sdnNjb2RlLXJlbW90ZQ%3D%3D",
+        )?;
+        assert_generate(
+            "file:///nb.ipynb",
+            u32::MAX,
+            "vscode-notebook-cell:/nb.ipynb#z211301422353sZmlsZQ%3D%3D",
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        fn assert_parse(cell: &str, expected: Option<(&str, u32)>) -> Result<()> {
+            let parsed = CellUri::parse(&URI::parse(cell)?);
+            assert_eq!(
+                parsed
+                    .as_ref()
+                    .map(|(notebook, handle)| (notebook.to_string(false), *handle)),
+                expected.map(|(notebook, handle)| (notebook.to_string(), handle))
+            );
+            Ok(())
+        }
+
+        assert_parse(
+            "vscode-notebook-cell:/Users/me/nb.ipynb#W0sZmlsZQ%3D%3D",
+            Some(("file:///Users/me/nb.ipynb", 0)),
+        )?;
+        assert_parse(
+            "vscode-notebook-cell:/Users/me/nb.ipynb#X10sZmlsZQ",
+            Some(("file:///Users/me/nb.ipynb", 7)),
+        )?;
+        assert_parse(
+            "vscode-notebook-cell:Untitled-1.ipynb#W2sdW50aXRsZWQ%3D",
+            Some(("untitled:Untitled-1.ipynb", 2)),
+        )?;
+        assert_parse(
+            "vscode-notebook-cell://wsl%2Bubuntu/home/me/nb.ipynb#Y202sdnNjb2RlLXJlbW90ZQ%3D%3D",
+            Some(("vscode-remote://wsl%2Bubuntu/home/me/nb.ipynb", 100)),
+        )?;
+
+        assert_parse("file:///Users/me/nb.ipynb#W0sZmlsZQ%3D%3D", None)?;
+        assert_parse("vscode-notebook-cell:/Users/me/nb.ipynb", None)?;
+        assert_parse(
+            "vscode-notebook-cell:/Users/me/nb.ipynb#WsZmlsZQ%3D%3D",
+            None,
+        )?;
+        assert_parse("vscode-notebook-cell:/Users/me/nb.ipynb#W0s!!", None)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip() -> Result<()> {
+        let notebook = URI::parse("vscode-vfs://github/microsoft/vscode/nb.ipynb?ref=main")?;
+        for handle in [0, 1, 7, 48, 1000, 123456, u32::MAX] {
+            let cell = CellUri::generate(&notebook, handle)?;
+            assert_eq!(CellUri::parse(&cell), Some((notebook.clone(), handle)));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use vscode_uri_rs::path::{ParsedPath, Posix, Win32};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(flavor: &str, function: &str, args: &[&str]) -> String {
+        let arg = || args[0];
+        let parse = |path: &str| match flavor {
+            "posix" => Posix::parse(path),
+            _ => Win32::parse(path),
+        };
+        match (flavor, function) {
+            ("posix", "join") => Posix::join(args),
+            ("win32", "join") => Win32::join(args),
+            ("posix", "resolve") => Posix::resolve(args),
+            ("win32", "resolve") => Win32::resolve(args),
+            ("posix", "relative") => Posix::relative(args[0], args[1]),
+            ("win32", "relative") => Win32::relative(args[0], args[1]),
+            ("posix", "normalize") => Posix::normalize(arg()),
+            ("win32", "normalize") => Win32::normalize(arg()),
+            ("posix", "isAbsolute") => Posix::is_absolute(arg()).to_string(),
+            ("win32", "isAbsolute") => Win32::is_absolute(arg()).to_string(),
+            ("posix", "dirname") => Posix::dirname(arg()),
+            ("win32", "dirname") => Win32::dirname(arg()),
+            ("posix", "basename") => Posix::basename(arg(), args.get(1).copied()),
+            ("win32", "basename") => Win32::basename(arg(), args.get(1).copied()),
+            ("posix", "extname") => Posix::extname(arg()),
+            ("win32", "extname") => Win32::extname(arg()),
+            ("posix", "toNamespacedPath") => Posix::to_namespaced_path(arg()),
+            ("win32", "toNamespacedPath") => Win32::to_namespaced_path(arg()),
+            ("posix", "format") => Posix::format(&Posix::parse(arg())),
+            ("win32", "format") => Win32::format(&Win32::parse(arg())),
+            (_, "parse.root") => parse(arg()).root,
+            (_, "parse.dir") => parse(arg()).dir,
+            (_, "parse.base") => parse(arg()).base,
+            (_, "parse.ext") => parse(arg()).ext,
+            (_, "parse.name") => parse(arg()).name,
+            _ => panic!("unknown function {}.{}", flavor, function),
+        }
+    }
+
+    #[test]
+    fn test_node_path_vectors() {
+        let vectors = include_str!("data/path_vectors.tsv");
+        let mut count = 0;
+        for line in vectors.lines() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let (flavor, function, expected, args) =
+                (fields[0], fields[1], fields[2], &fields[3..]);
+            assert_eq!(
+                call(flavor, function, args),
+                expected,
+                "{}.{}({:?})",
+                flavor,
+                function,
+                args
+            );
+            count += 1;
+        }
+        assert!(count > 5000);
+    }
+
+    #[test]
+    fn test_format() {
+        // from Node's test-path-parse-format.js
+        let path = |root: &str, dir: &str, base: &str, ext: &str, name: &str| ParsedPath {
+            root: root.to_string(),
+            dir: dir.to_string(),
+            base: base.to_string(),
+            ext: ext.to_string(),
+            name: name.to_string(),
+        };
+        assert_eq!(Posix::format(&path("/", "", "", "", "")), "/");
+        assert_eq!(Posix::format(&path("", "", "", ".ext", "")), ".ext");
+        assert_eq!(
+            Posix::format(&path("", "some/dir", "", "", "")),
+            "some/dir/"
+        );
+        assert_eq!(Posix::format(&path("/", "", "", ".txt", "x")), "/x.txt");
+        assert_eq!(Posix::format(&path("", "", "", "png", "x")), "x.png");
+        assert_eq!(Posix::format(&path("", "", "", ".png", "x")), "x.png");
+        assert_eq!(
+            Posix::format(&path("/", "/dir", "b.md", ".txt", "a")),
+            "/dir/b.md"
+        );
+        assert_eq!(
+            Win32::format(&path("", "some\\dir", "", "", "")),
+            "some\\dir\\"
+        );
+        assert_eq!(
+            Win32::format(&path("C:\\", "", "x.png", "", "")),
+            "C:\\x.png"
+        );
+        assert_eq!(
+            Win32::format(&path("", "C:\\a", "", "ts", "b")),
+            "C:\\a\\b.ts"
+        );
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(Posix::SEP, "/");
+        assert_eq!(Posix::DELIMITER, ":");
+        assert_eq!(Win32::SEP, "\\");
+        assert_eq!(Win32::DELIMITER, ";");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_resolve_relative_to_cwd() {
+        let cwd = std::env::current_dir().unwrap();
+        let cwd = cwd.to_str().unwrap();
+        assert_eq!(Posix::resolve(&[]), cwd);
+        assert_eq!(Posix::resolve(&["a", "..", "b"]), format!("{}/b", cwd));
+        assert_eq!(Posix::relative(cwd, "b"), "b");
+        assert_eq!(Posix::relative("", ""), "");
+    }
+}
@@ -1,4 +1,4 @@
-use vscode_uri_rs::{URIComponents, UriError, Utils, URI};
+use vscode_uri_rs::{ExtnameOptions, URIComponents, UriError, Utils, URI};
 type Result<T> = std::result::Result<T, UriError>;
 
 #[cfg(test)]
@@ -127,6 +127,102 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_stem_and_extnames() -> Result<()> {
+        fn assert_parts(input: &str, stem: &str, extnames: &str) -> Result<()> {
+            let test_uri = URI::parse(input)?;
+            assert_eq!(Utils::stem(&test_uri), stem, "{}", input);
+            assert_eq!(Utils::extnames(&test_uri), extnames, "{}", input);
+            Ok(())
+        }
+
+        assert_parts("foo://a/foo/bar", "bar", "")?;
+        assert_parts("foo://a/foo/bar.ts", "bar", ".ts")?;
+        assert_parts("foo://a/foo/bar.d.ts", "bar", ".d.ts")?;
+        assert_parts("foo://a/foo/bar.spec.ts/", "bar", ".spec.ts")?;
+        assert_parts("foo://a/foo/backup.TAR.GZ", "backup", ".TAR.GZ")?;
+        assert_parts("foo://a/foo/bar.e2e.ts", "bar.e2e", ".ts")?;
+        assert_parts("foo://a/foo/.gitignore", ".gitignore", "")?;
+        assert_parts("foo://a/foo/.eslintrc.json", ".eslintrc", ".json")?;
+        // a compound extension needs a stem
+        assert_parts("foo://a/foo/.d.ts", ".d", ".ts")?;
+        assert_parts("foo://a/foo/..d.ts", "..d", ".ts")?;
+        assert_parts("foo://a/foo/bar.", "bar", ".")?;
+        assert_parts("foo://a/", "", "")?;
+
+        let options = ExtnameOptions {
+            compound_extensions: &[".stories.tsx"],
+        };
+        let uri = URI::parse("foo://a/Button.stories.tsx")?;
+        assert_eq!(Utils::extnames_with_options(&uri, &options), ".stories.tsx");
+        assert_eq!(Utils::stem_with_options(&uri, &options), "Button");
+        assert_eq!(Utils::extnames(&uri), ".tsx");
+        let uri = URI::parse("foo://a/index.d.ts")?;
+        assert_eq!(Utils::extnames_with_options(&uri, &options), ".ts");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_extnames_and_stem() -> Result<()> {
+        let uri = URI::parse("foo://a/src/foo.test.ts?q#f")?;
+        let source = Utils::with_extnames(&uri, ".ts")?;
+        assert_eq!(source.to_string(false), "foo://a/src/foo.ts?q#f");
+        assert_eq!(Utils::with_extnames(&source, "test.ts")?, uri);
+        assert_eq!(
+            Utils::with_extnames(&source, "")?.to_string(false),
+            "foo://a/src/foo?q#f"
+        );
+        assert_eq!(
+            Utils::with_stem(&uri, "bar")?.to_string(false),
+            "foo://a/src/bar.test.ts?q#f"
+        );
+        assert_eq!(
+            Utils::with_stem(&URI::parse("foo://a/src/")?, "lib")?.to_string(false),
+            "foo://a/lib/"
+        );
+        assert_eq!(
+            Utils::with_extnames(&URI::parse("foo://a/.gitignore")?, ".bak")?.to_string(false),
+            "foo://a/.gitignore.bak"
+        );
+        let options = ExtnameOptions {
+            compound_extensions: &[],
+        };
+        assert_eq!(
+            Utils::with_extnames_with_options(&uri, ".js", &options)?.to_string(false),
+            "foo://a/src/foo.test.js?q#f"
+        );
+        assert_eq!(
+            Utils::with_stem_with_options(&uri, "bar", &options)?.to_string(false),
+            "foo://a/src/bar.ts?q#f"
+        );
+
+        assert!(Utils::with_stem(&uri, "").is_err());
+        assert!(Utils::with_stem(&uri, "a/b").is_err());
+        assert!(Utils::with_extnames(&uri, ".a/b").is_err());
+        assert!(Utils::with_extnames(&URI::parse("foo://a/")?, ".ts").is_err());
+        assert!(Utils::with_stem(&URI::parse("foo:")?, "a").is_err());
+
+        // `.` and `..` are not file names
+        let src = URI::parse("file:///src/foo")?;
+        assert!(Utils::with_stem(&src, "..").is_err());
+        assert!(Utils::with_stem(&src, ".").is_err());
+        assert!(Utils::with_extnames(&URI::parse("file:///src/.")?, "ts").is_err());
+        assert!(Utils::with_stem(&URI::parse("file:///src/..")?, "a").is_err());
+
+        // unlike `URI::with_extname`, compound extensions are replaced as a whole
+        assert_eq!(
+            uri.with_extname("js")?.to_string(false),
+            "foo://a/src/foo.test.js?q#f"
+        );
+        assert_eq!(
+            Utils::with_extnames(&uri, "js")?.to_string(false),
+            "foo://a/src/foo.js?q#f"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_basename() -> Result<()> {
         fn assert_basename(input: &str, expected: &str) -> Result<()> {
@@ -0,0 +1,92 @@
+use vscode_uri_rs::platform::{is_windows, path_style, with_path_style, PathStyle};
+use vscode_uri_rs::{UriError, URI};
+type Result<T> = std::result::Result<T, UriError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_path_style() -> Result<()> {
+        assert_eq!(path_style(), PathStyle::NATIVE);
+
+        let uri = with_path_style(PathStyle::Windows, || {
+            assert!(is_windows());
+            // nested overrides are restored in turn
+            with_path_style(PathStyle::Posix, || assert!(!is_windows()));
+            assert_eq!(path_style(), PathStyle::Windows);
+            URI::file("C:\\Users\\me")
+        })?;
+        assert_eq!(path_style(), PathStyle::NATIVE);
+        assert_eq!(uri.to_string(false), "file:///c%3A/Users/me");
+
+        let fs_path = with_path_style(PathStyle::Posix, || uri.fs_path());
+        assert_eq!(fs_path.to_string_lossy(), "c:/Users/me");
+        let fs_path = with_path_style(PathStyle::Windows, || uri.fs_path());
+        assert_eq!(fs_path.to_string_lossy(), "c:\\Users\\me");
+        Ok(())
+    }
+
+    #[test]
+    fn test_path_style_is_thread_local() {
+        with_path_style(PathStyle::Windows, || {
+            let other = std::thread::spawn(path_style).join().unwrap();
+            assert_eq!(other, PathStyle::NATIVE);
+            assert_eq!(path_style(), PathStyle::Windows);
+        });
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                std::thread::spawn(move || {
+                    let style = if i % 2 == 0 {
+                        PathStyle::Posix
+                    } else {
+                        PathStyle::Windows
+                    };
+                    with_path_style(style, || {
+                        for _ in 0..100 {
+                            let uri = URI::file("\\a\\b").unwrap();
+                            let expected = match style {
+                                PathStyle::Posix => "file:///%5Ca%5Cb",
+                                PathStyle::Windows => "file:///a/b",
+                            };
+                            assert_eq!(uri.to_string(false), expected);
+                        }
+                    });
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_path_style_restored_after_panic() {
+        let result = std::panic::catch_unwind(|| {
+            with_path_style(PathStyle::Windows, || panic!("failed"));
+        });
+        assert!(result.is_err());
+        assert_eq!(path_style(), PathStyle::NATIVE);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_set_is_windows() {
+        use vscode_uri_rs::platform::test_utils;
+
+        // the style stays set on the thread, so the test runs on a thread of its own
+        std::thread::spawn(|| {
+            test_utils::set_is_windows(true);
+            assert!(test_utils::is_windows());
+            assert_eq!(
+                URI::file("C:\\a").unwrap().to_string(false),
+                "file:///c%3A/a"
+            );
+            test_utils::set_is_windows(false);
+            assert_eq!(path_style(), PathStyle::Posix);
+        })
+        .join()
+        .unwrap();
+    }
+}
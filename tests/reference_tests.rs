@@ -0,0 +1,121 @@
+use vscode_uri_rs::{UriError, UriReference, URI};
+type Result<T> = std::result::Result<T, UriError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(reference: &str, base: &URI) -> Result<URI> {
+        URI::parse_reference(reference)?.resolve(base)
+    }
+
+    #[test]
+    fn test_parse_reference() -> Result<()> {
+        let reference = URI::parse_reference("foo/bar")?;
+        assert!(reference.is_relative());
+        let UriReference::Relative(relative) = &reference else {
+            panic!("expected a relative reference");
+        };
+        assert_eq!(relative.authority(), None);
+        assert_eq!(relative.path(), "foo/bar");
+        assert_eq!(relative.query(), None);
+        assert_eq!(relative.fragment(), None);
+        assert_eq!(reference.to_string(), "foo/bar");
+
+        let UriReference::Relative(relative) = URI::parse_reference("//Host/a%20b?#x")? else {
+            panic!("expected a relative reference");
+        };
+        assert_eq!(relative.authority(), Some("Host"));
+        assert_eq!(relative.path(), "/a b");
+        assert_eq!(relative.query(), Some(""));
+        assert_eq!(relative.fragment(), Some("x"));
+        assert_eq!(relative.to_string(), "//host/a%20b?#x");
+
+        // a colon in the first segment stays encoded
+        let reference = URI::parse_reference("./a%3Ab")?;
+        assert_eq!(reference.to_string(), "./a%3Ab");
+
+        let reference = URI::parse_reference("https://example.com/a")?;
+        assert!(!reference.is_relative());
+        assert_eq!(
+            reference,
+            UriReference::Absolute(URI::parse("https://example.com/a")?)
+        );
+        assert!(URI::parse_reference("a b:c").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_rfc3986_examples() -> Result<()> {
+        // RFC 3986, section 5.4
+        let base = URI::parse("http://a/b/c/d;p?q")?;
+        for (reference, expected) in [
+            ("g:h", "g:h"),
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            ("//g", "http://g"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"),
+            ("g#s", "http://a/b/c/g#s"),
+            ("g?y#s", "http://a/b/c/g?y#s"),
+            (";x", "http://a/b/c/;x"),
+            ("g;x", "http://a/b/c/g;x"),
+            ("g;x?y#s", "http://a/b/c/g;x?y#s"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../..", "http://a/"),
+            ("../../", "http://a/"),
+            ("../../g", "http://a/g"),
+            ("../../../g", "http://a/g"),
+            ("../../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("/../g", "http://a/g"),
+            ("g.", "http://a/b/c/g."),
+            (".g", "http://a/b/c/.g"),
+            ("g..", "http://a/b/c/g.."),
+            ("..g", "http://a/b/c/..g"),
+            ("./../g", "http://a/b/g"),
+            ("./g/.", "http://a/b/c/g/"),
+            ("g/./h", "http://a/b/c/g/h"),
+            ("g/../h", "http://a/b/c/h"),
+            ("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+            ("g;x=1/../y", "http://a/b/c/y"),
+            ("g?y/./x", "http://a/b/c/g?y/./x"),
+            ("g?y/../x", "http://a/b/c/g?y/../x"),
+            ("g#s/./x", "http://a/b/c/g#s/./x"),
+            ("g#s/../x", "http://a/b/c/g#s/../x"),
+        ] {
+            assert_eq!(
+                resolve(reference, &base)?,
+                URI::parse(expected)?,
+                "{}",
+                reference
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_against_file_uri() -> Result<()> {
+        let base = URI::parse("file:///home/me/project/settings.json")?;
+        assert_eq!(
+            resolve("../shared/tasks.json", &base)?.to_string(false),
+            "file:///home/me/shared/tasks.json"
+        );
+        assert_eq!(
+            resolve("src/main.rs#L10", &base)?.to_string(false),
+            "file:///home/me/project/src/main.rs#L10"
+        );
+
+        // `parse` takes the same value for an absolute path
+        assert_eq!(URI::parse("foo/bar")?.to_string(false), "file:///foo/bar");
+        Ok(())
+    }
+}
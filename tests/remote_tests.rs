@@ -0,0 +1,214 @@
+use vscode_uri_rs::platform::{with_path_style, PathStyle};
+use vscode_uri_rs::remote::{
+    RemoteAuthority, RemoteMapping, RemoteMappings, SshMapping, WslMapping, WSL_LOCALHOST_UNC_HOST,
+};
+use vscode_uri_rs::{UriError, URI};
+
+type Result<T> = std::result::Result<T, UriError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_authority() {
+        let authority = RemoteAuthority::parse("wsl+Ubuntu-22.04").unwrap();
+        assert_eq!(authority.kind(), "wsl");
+        assert_eq!(authority.value(), "Ubuntu-22.04");
+        assert_eq!(authority.to_string(), "wsl+Ubuntu-22.04");
+
+        let authority = RemoteAuthority::parse("ssh-remote+user@host+with+plus").unwrap();
+        assert_eq!(authority.kind(), "ssh-remote");
+        assert_eq!(authority.value(), "user@host+with+plus");
+        assert_eq!(
+            RemoteAuthority::ssh("my-host").to_string(),
+            "ssh-remote+my-host"
+        );
+
+        assert_eq!(RemoteAuthority::parse("localhost"), None);
+        assert_eq!(RemoteAuthority::parse("+foo"), None);
+
+        let uri = URI::parse("vscode-remote://wsl+Ubuntu/home/me").unwrap();
+        assert_eq!(
+            RemoteAuthority::of(&uri),
+            Some(RemoteAuthority::wsl("Ubuntu"))
+        );
+        let uri = URI::parse("file://wsl+Ubuntu/home/me").unwrap();
+        assert_eq!(RemoteAuthority::of(&uri), None);
+    }
+
+    #[test]
+    fn test_wsl_to_remote() -> Result<()> {
+        let wsl = WslMapping::new("Ubuntu");
+        fn assert_to_remote(wsl: &WslMapping, local: &str, expected: Option<&str>) -> Result<()> {
+            let remote = wsl.to_remote(&URI::parse(local)?)?;
+            assert_eq!(remote.map(|uri| uri.to_string(true)).as_deref(), expected);
+            Ok(())
+        }
+
+        assert_to_remote(
+            &wsl,
+            "file://wsl$/Ubuntu/home/me/a.ts",
+            Some("vscode-remote://wsl+ubuntu/home/me/a.ts"),
+        )?;
+        assert_to_remote(
+            &wsl,
+            "file://wsl.localhost/ubuntu/home/me/a.ts?q#f",
+            Some("vscode-remote://wsl+ubuntu/home/me/a.ts?q#f"),
+        )?;
+        assert_to_remote(
+            &wsl,
+            "file://wsl$/Ubuntu",
+            Some("vscode-remote://wsl+ubuntu/"),
+        )?;
+        assert_to_remote(
+            &wsl,
+            "file:///c%3A/Users/me",
+            Some("vscode-remote://wsl+ubuntu/mnt/c/Users/me"),
+        )?;
+        assert_to_remote(&wsl, "file:///C:", Some("vscode-remote://wsl+ubuntu/mnt/c"))?;
+        assert_to_remote(&wsl, "file://wsl$/Debian/home/me", None)?;
+        assert_to_remote(&wsl, "file://server/share/a.ts", None)?;
+        assert_to_remote(&wsl, "file:///home/me", None)?;
+        assert_to_remote(&wsl, "https://wsl$/Ubuntu/home", None)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wsl_to_local() -> Result<()> {
+        let wsl = WslMapping::new("Ubuntu");
+        fn assert_to_local(wsl: &WslMapping, remote: &str, expected: Option<&str>) -> Result<()> {
+            let local = wsl.to_local(&URI::parse(remote)?)?;
+            assert_eq!(local.map(|uri| uri.to_string(true)).as_deref(), expected);
+            Ok(())
+        }
+
+        assert_to_local(
+            &wsl,
+            "vscode-remote://wsl+Ubuntu/home/me/a.ts#L3",
+            Some("file://wsl$/Ubuntu/home/me/a.ts#L3"),
+        )?;
+        assert_to_local(
+            &wsl,
+            "vscode-remote://wsl+ubuntu/",
+            Some("file://wsl$/Ubuntu"),
+        )?;
+        assert_to_local(
+            &wsl,
+            "vscode-remote://wsl+Ubuntu/mnt/c/Users/me",
+            Some("file:///c:/Users/me"),
+        )?;
+        assert_to_local(
+            &wsl,
+            "vscode-remote://wsl+Ubuntu/mnt/d",
+            Some("file:///d:/"),
+        )?;
+        assert_to_local(
+            &wsl,
+            "vscode-remote://wsl+Ubuntu/mnt/wsl/share",
+            Some("file://wsl$/Ubuntu/mnt/wsl/share"),
+        )?;
+        assert_to_local(&wsl, "vscode-remote://wsl+Debian/home/me", None)?;
+        assert_to_local(&wsl, "vscode-remote://ssh-remote+Ubuntu/home/me", None)?;
+
+        let wsl = WslMapping::with_unc_host("Ubuntu", WSL_LOCALHOST_UNC_HOST);
+        assert_to_local(
+            &wsl,
+            "vscode-remote://wsl+Ubuntu/home/me",
+            Some("file://wsl.localhost/Ubuntu/home/me"),
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wsl_fs_paths() -> Result<()> {
+        let wsl = WslMapping::new("Ubuntu");
+        let (remote, remote_file) = with_path_style(PathStyle::Windows, || -> Result<_> {
+            let local = URI::file("\\\\wsl$\\Ubuntu\\home\\me\\a.ts")?;
+            let remote = wsl.to_remote(&local)?.unwrap();
+            assert_eq!(
+                remote.to_string(false),
+                "vscode-remote://wsl%2Bubuntu/home/me/a.ts"
+            );
+            let local = wsl.to_local(&remote)?.unwrap();
+            assert_eq!(
+                local.fs_path().to_string_lossy(),
+                "\\\\wsl$\\Ubuntu\\home\\me\\a.ts"
+            );
+
+            let local = URI::file("C:\\Users\\me")?;
+            let remote = wsl.to_remote(&local)?.unwrap();
+            let remote_file = wsl.to_remote_file(&remote)?.unwrap();
+            Ok((remote, remote_file))
+        })?;
+
+        with_path_style(PathStyle::Posix, || {
+            assert_eq!(remote_file.fs_path().to_string_lossy(), "/mnt/c/Users/me");
+            assert_eq!(wsl.remote_file_to_remote(&remote_file)?, Some(remote));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_ssh() -> Result<()> {
+        let ssh = SshMapping::new("my-host");
+        let remote = URI::parse("vscode-remote://ssh-remote+my-host/home/me/a.ts")?;
+        assert_eq!(ssh.to_local(&remote)?, None);
+        assert_eq!(
+            ssh.to_remote_file(&remote)?
+                .map(|uri| uri.to_string(false))
+                .as_deref(),
+            Some("file:///home/me/a.ts")
+        );
+        assert_eq!(
+            ssh.remote_file_to_remote(&URI::file("/home/me/a.ts")?)?
+                .map(|uri| uri.to_string(false))
+                .as_deref(),
+            Some("vscode-remote://ssh-remote%2Bmy-host/home/me/a.ts")
+        );
+        assert_eq!(ssh.to_remote(&URI::file("/home/me/a.ts")?)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_remote_mappings() -> Result<()> {
+        let mut mappings = RemoteMappings::new();
+        mappings.register(WslMapping::new("Ubuntu"));
+        mappings.register(WslMapping::new("Debian"));
+        mappings.register(SshMapping::new("my-host"));
+
+        let remote = URI::parse("vscode-remote://wsl+debian/home/me")?;
+        assert_eq!(
+            mappings
+                .to_local(&remote)?
+                .map(|uri| uri.to_string(true))
+                .as_deref(),
+            Some("file://wsl$/Debian/home/me")
+        );
+        let local = URI::parse("file://wsl$/Debian/home/me")?;
+        assert_eq!(
+            mappings
+                .to_remote(&local)?
+                .map(|uri| uri.to_string(true))
+                .as_deref(),
+            Some("vscode-remote://wsl+debian/home/me")
+        );
+        let remote = URI::parse("vscode-remote://ssh-remote+my-host/etc/hosts")?;
+        assert_eq!(mappings.to_local(&remote)?, None);
+        assert_eq!(
+            mappings
+                .to_remote_file(&remote)?
+                .map(|uri| uri.to_string(true))
+                .as_deref(),
+            Some("file:///etc/hosts")
+        );
+        let remote = URI::parse("vscode-remote://dev-container+abc/workspace")?;
+        assert!(mappings.find(&remote).is_none());
+        assert_eq!(mappings.to_local(&remote)?, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use vscode_uri_rs::{
+    ErrorContext, Schemas, SchemeRegistry, SchemeRules, UriComponent, UriError, URI,
+};
+type Result<T> = std::result::Result<T, UriError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_rules() {
+        let file = SchemeRegistry::rules(Schemas::FILE);
+        assert!(file.absolute_path);
+        assert!(file.authority_slashes);
+        assert!(!file.requires_authority);
+
+        assert_eq!(SchemeRegistry::rules(Schemas::HTTP).default_port, Some(80));
+        assert_eq!(
+            SchemeRegistry::rules(Schemas::HTTPS).default_port,
+            Some(443)
+        );
+        assert_eq!(SchemeRegistry::rules("HTTPS"), SchemeRules::default());
+
+        assert!(!SchemeRegistry::is_registered(Schemas::UNTITLED));
+        assert_eq!(
+            SchemeRegistry::rules(Schemas::UNTITLED),
+            SchemeRules::default()
+        );
+    }
+
+    #[test]
+    fn test_scheme_case_folding() -> Result<()> {
+        // like in vscode-uri, the rules of built-in schemes only apply to the lowercase names
+        let uri = URI::parse("HTTP:foo")?;
+        assert_eq!(uri.path(), "foo");
+        assert_eq!(uri.to_string(false), "HTTP:foo");
+        assert_eq!(URI::parse("FILE:foo")?.to_string(false), "FILE:foo");
+        assert_eq!(URI::parse("File://host")?.to_string(false), "File://host");
+        let uri = URI::parse("FILE://server/share")?;
+        assert_eq!(uri.authority(), "server");
+        assert_eq!(uri.to_string(false), "FILE://server/share");
+
+        // registered rules apply to any case
+        let rules = SchemeRules {
+            absolute_path: true,
+            ..Default::default()
+        };
+        SchemeRegistry::with_rules("mem", rules, || {
+            assert_eq!(URI::parse("MEM:foo")?.path(), "/foo");
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_custom_scheme_requires_authority() -> Result<()> {
+        let rules = SchemeRules {
+            requires_authority: true,
+            ..Default::default()
+        };
+        SchemeRegistry::with_rules("test-requires-authority", rules, || {
+            assert!(SchemeRegistry::is_registered("test-requires-authority"));
+            assert!(URI::parse("test-requires-authority://host/path").is_ok());
+            assert_eq!(
+                URI::parse("test-requires-authority:/path"),
+                Err(UriError::MissingAuthority {
+                    scheme: "test-requires-authority".to_string(),
+                    context: Box::new(ErrorContext {
+                        input: "test-requires-authority:/path".to_string(),
+                        span: 24..24,
+                        component: UriComponent::Authority,
+                    }),
+                })
+            );
+            let uri = URI::parse("test-requires-authority://host/path")?;
+            assert!(uri
+                .with(vscode_uri_rs::URIChange {
+                    authority: Some(String::new()),
+                    ..Default::default()
+                })
+                .is_err());
+
+            // other threads do not see the rules
+            let other = std::thread::spawn(|| URI::parse("test-requires-authority:/path").is_ok());
+            assert!(other.join().unwrap());
+            Ok(())
+        })?;
+
+        assert!(!SchemeRegistry::is_registered("test-requires-authority"));
+        assert!(URI::parse("test-requires-authority:/path").is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_scoped_rules_nest() {
+        let absolute = SchemeRules {
+            absolute_path: true,
+            ..Default::default()
+        };
+        SchemeRegistry::with_rules("test-scoped", absolute, || {
+            SchemeRegistry::with_rules("TEST-SCOPED", SchemeRules::default(), || {
+                assert_eq!(SchemeRegistry::rules("test-scoped"), SchemeRules::default());
+            });
+            assert_eq!(SchemeRegistry::rules("test-scoped"), absolute);
+            // scoped rules take precedence over built-in ones
+            SchemeRegistry::with_rules(Schemas::HTTP, absolute, || {
+                assert_eq!(SchemeRegistry::rules(Schemas::HTTP).default_port, None);
+            });
+        });
+        assert!(!SchemeRegistry::is_registered("test-scoped"));
+    }
+
+    #[test]
+    fn test_custom_scheme_absolute_path() -> Result<()> {
+        assert_eq!(URI::parse("test-absolute-path:foo")?.path(), "foo");
+
+        SchemeRegistry::register(
+            "Test-Absolute-Path",
+            SchemeRules {
+                absolute_path: true,
+                authority_slashes: true,
+                ..Default::default()
+            },
+        );
+        let uri = URI::parse("test-absolute-path:foo")?;
+        assert_eq!(uri.path(), "/foo");
+        assert_eq!(uri.to_string(false), "test-absolute-path:///foo");
+        assert_eq!(
+            URI::new("test-absolute-path", "", "", "", "")?.to_string(false),
+            "test-absolute-path:///"
+        );
+
+        assert!(SchemeRegistry::unregister("test-absolute-path").is_some());
+        assert_eq!(URI::parse("test-absolute-path:foo")?.path(), "foo");
+
+        // built-in rules can be overridden, but not removed
+        assert_eq!(SchemeRegistry::unregister(Schemas::FILE), None);
+        assert!(SchemeRegistry::rules(Schemas::FILE).absolute_path);
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+use vscode_uri_rs::glob::{Glob, RelativePattern};
+use vscode_uri_rs::selector::{matches, score, DocumentCandidate, DocumentFilter};
+use vscode_uri_rs::{UriError, URI};
+type Result<T> = std::result::Result<T, UriError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(
+        language: Option<&str>,
+        scheme: Option<&str>,
+        pattern: Option<&str>,
+    ) -> DocumentFilter {
+        DocumentFilter {
+            language: language.map(|s| s.to_string()),
+            scheme: scheme.map(|s| s.to_string()),
+            pattern: pattern.map(|p| Glob::new(p).unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_score_invalid_selector() -> Result<()> {
+        let uri = URI::parse("file:///testbed/file.fb")?;
+        let candidate = DocumentCandidate::new(&uri, "farboo");
+        assert_eq!(score(&[], &candidate), 0);
+        assert_eq!(score(&[DocumentFilter::default()], &candidate), 0);
+        assert_eq!(score(&[DocumentFilter::language("")], &candidate), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_score_any_language() -> Result<()> {
+        let uri = URI::parse("file:///testbed/file.fb")?;
+        let candidate = DocumentCandidate::new(&uri, "farboo");
+        assert_eq!(score(&[DocumentFilter::language("*")], &candidate), 5);
+
+        let uri = URI::parse("foo:bar")?;
+        let candidate = DocumentCandidate::new(&uri, "farboo");
+        assert_eq!(score(&[DocumentFilter::language("*")], &candidate), 5);
+        assert_eq!(score(&[DocumentFilter::language("farboo")], &candidate), 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_score_default_schemes() -> Result<()> {
+        let uri = URI::parse("git:foo/file.txt")?;
+        let candidate = DocumentCandidate::new(&uri, "farboo");
+
+        assert_eq!(score(&[filter(Some("*"), None, None)], &candidate), 5);
+        assert_eq!(score(&[filter(Some("farboo"), None, None)], &candidate), 10);
+        assert_eq!(
+            score(&[filter(Some("farboo"), Some(""), None)], &candidate),
+            10
+        );
+        assert_eq!(
+            score(&[filter(Some("farboo"), Some("git"), None)], &candidate),
+            10
+        );
+        assert_eq!(
+            score(&[filter(Some("farboo"), Some("*"), None)], &candidate),
+            10
+        );
+        assert_eq!(score(&[filter(None, Some("*"), None)], &candidate), 5);
+        assert_eq!(score(&[filter(None, Some("git"), None)], &candidate), 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_score_filter() -> Result<()> {
+        let uri = URI::parse("file:///testbed/file.fb")?;
+        let candidate = DocumentCandidate::new(&uri, "farboo");
+
+        assert_eq!(
+            score(&[filter(Some("farboo"), Some("file"), None)], &candidate),
+            10
+        );
+        assert_eq!(
+            score(&[filter(Some("farboo"), Some("http"), None)], &candidate),
+            0
+        );
+        assert_eq!(
+            score(&[filter(None, None, Some("**/*.fb"))], &candidate),
+            10
+        );
+        assert_eq!(
+            score(&[filter(None, Some("file"), Some("**/*.fb"))], &candidate),
+            10
+        );
+        assert_eq!(
+            score(&[filter(None, None, Some("/testbed/file.fb"))], &candidate),
+            10
+        );
+
+        let uri = URI::parse("foo:bar")?;
+        let candidate = DocumentCandidate::new(&uri, "farboo");
+        assert_eq!(score(&[filter(None, None, Some("**/*.fb"))], &candidate), 0);
+        assert_eq!(
+            score(&[filter(None, Some("foo"), Some("**/*.fb"))], &candidate),
+            0
+        );
+
+        let uri = URI::parse("git:/my/file.js")?;
+        let candidate = DocumentCandidate::new(&uri, "javascript");
+        assert_eq!(
+            score(&[DocumentFilter::language("javascript")], &candidate),
+            10
+        );
+        assert_eq!(
+            score(&[filter(Some("javascript"), Some("git"), None)], &candidate),
+            10
+        );
+        assert_eq!(score(&[DocumentFilter::language("*")], &candidate), 5);
+        assert_eq!(score(&[DocumentFilter::language("fooLang")], &candidate), 0);
+        assert_eq!(
+            score(
+                &[
+                    DocumentFilter::language("fooLang"),
+                    DocumentFilter::language("*")
+                ],
+                &candidate
+            ),
+            5
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_score_max_filters() -> Result<()> {
+        let uri = URI::parse("file:///testbed/file.fb")?;
+        let candidate = DocumentCandidate::new(&uri, "farboo");
+        let pass = filter(Some("farboo"), Some("file"), None);
+        let fail = filter(Some("farboo"), Some("http"), None);
+
+        assert_eq!(score(&[pass.clone(), fail.clone()], &candidate), 10);
+        assert_eq!(score(&[fail.clone(), fail.clone()], &candidate), 0);
+        assert!(matches(&[fail.clone(), pass], &candidate));
+        assert!(!matches(&[fail], &candidate));
+        assert_eq!(
+            score(
+                &[
+                    DocumentFilter::language("*"),
+                    DocumentFilter::language("farboo")
+                ],
+                &candidate
+            ),
+            10
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_score_has_access_to_all_models() -> Result<()> {
+        let uri = URI::parse("file:/my/file.js")?;
+        let candidate = DocumentCandidate {
+            is_synchronized: false,
+            ..DocumentCandidate::new(&uri, "javascript")
+        };
+
+        assert_eq!(
+            score(&[DocumentFilter::language("javascript")], &candidate),
+            0
+        );
+        assert_eq!(
+            score(
+                &[filter(Some("javascript"), Some("file"), None)],
+                &candidate
+            ),
+            0
+        );
+        assert_eq!(score(&[DocumentFilter::language("*")], &candidate), 0);
+        assert_eq!(
+            score(
+                &[DocumentFilter {
+                    has_access_to_all_models: true,
+                    ..filter(Some("javascript"), Some("file"), None)
+                }],
+                &candidate
+            ),
+            10
+        );
+        assert_eq!(
+            score(
+                &[
+                    DocumentFilter::language("fooLang"),
+                    DocumentFilter::language("*"),
+                    DocumentFilter {
+                        has_access_to_all_models: true,
+                        ..DocumentFilter::language("*")
+                    }
+                ],
+                &candidate
+            ),
+            5
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_score_notebook_type() -> Result<()> {
+        let uri = URI::parse("vscode-notebook-cell:///my/file.js#blabla")?;
+        let notebook_uri = URI::parse("file:///my/file.js")?;
+        let plain = DocumentCandidate::new(&uri, "javascript");
+        let cell = DocumentCandidate {
+            notebook_uri: Some(&notebook_uri),
+            notebook_type: Some("fooBook"),
+            ..plain
+        };
+        let notebook_filter =
+            |notebook_type: &str, language: Option<&str>, scheme: Option<&str>| DocumentFilter {
+                notebook_type: Some(notebook_type.to_string()),
+                ..filter(language, scheme, None)
+            };
+
+        assert_eq!(score(&[DocumentFilter::language("javascript")], &plain), 10);
+        assert_eq!(score(&[DocumentFilter::language("javascript")], &cell), 10);
+        assert_eq!(score(&[notebook_filter("fooBook", None, None)], &cell), 10);
+        assert_eq!(
+            score(
+                &[notebook_filter("fooBook", Some("javascript"), Some("file"))],
+                &cell
+            ),
+            10
+        );
+        assert_eq!(
+            score(&[notebook_filter("fooBook", Some("*"), None)], &cell),
+            10
+        );
+        assert_eq!(score(&[notebook_filter("*", Some("*"), None)], &cell), 5);
+        assert_eq!(
+            score(&[notebook_filter("*", Some("javascript"), None)], &cell),
+            10
+        );
+        assert_eq!(score(&[notebook_filter("*", None, None)], &plain), 0);
+        assert_eq!(score(&[notebook_filter("barBook", None, None)], &cell), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_score_relative_pattern() -> Result<()> {
+        let uri = URI::file("/home/user/Desktop/test.json")?;
+        let candidate = DocumentCandidate::new(&uri, "json");
+        let relative = |base: &str| -> Result<DocumentFilter> {
+            Ok(DocumentFilter {
+                pattern: Some(Glob::relative(&RelativePattern::new(
+                    URI::file(base)?,
+                    "*.json",
+                ))?),
+                ..Default::default()
+            })
+        };
+
+        assert_eq!(score(&[relative("/home/user/Desktop")?], &candidate), 10);
+        assert_eq!(score(&[relative("/home/user/Desktop/")?], &candidate), 10);
+        assert_eq!(score(&[relative("/home/user")?], &candidate), 0);
+        assert_eq!(score(&[relative("/home/user/Documents")?], &candidate), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use js_sys::{Object, Reflect};
+use vscode_uri_rs::wasm::{WasmUri, WasmUtils};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+// Runs the tests in a headless browser, e.g. with
+// `wasm-pack test --headless --firefox --features wasm`
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn object(properties: &[(&str, JsValue)]) -> JsValue {
+    let object = Object::new();
+    for (key, value) in properties {
+        Reflect::set(&object, &JsValue::from_str(key), value).unwrap();
+    }
+    object.into()
+}
+
+fn property(object: &JsValue, key: &str) -> JsValue {
+    Reflect::get(object, &JsValue::from_str(key)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn test_parse_and_format() {
+        let uri = WasmUri::parse(
+            "https://code.visualstudio.com/docs/extensions/overview#frag",
+            None,
+        )
+        .unwrap();
+        assert_eq!(uri.scheme(), "https");
+        assert_eq!(uri.authority(), "code.visualstudio.com");
+        assert_eq!(uri.path(), "/docs/extensions/overview");
+        assert_eq!(uri.query(), "");
+        assert_eq!(uri.fragment(), "frag");
+        assert_eq!(
+            uri.to_string_js(None),
+            "https://code.visualstudio.com/docs/extensions/overview#frag"
+        );
+
+        let uri = WasmUri::parse("file:///c:/test/me%20now", None).unwrap();
+        assert_eq!(uri.to_string_js(None), "file:///c%3A/test/me%20now");
+        assert_eq!(uri.to_string_js(Some(true)), "file:///c:/test/me now");
+    }
+
+    #[wasm_bindgen_test]
+    fn test_file() {
+        let uri = WasmUri::file("/users/me/rust-projects/").unwrap();
+        assert_eq!(uri.scheme(), "file");
+        assert_eq!(uri.fs_path(), "/users/me/rust-projects/");
+        assert_eq!(uri.to_string_js(None), "file:///users/me/rust-projects/");
+    }
+
+    #[wasm_bindgen_test]
+    fn test_with() {
+        let uri = WasmUri::parse("foo://auth/path?query#fragment", None).unwrap();
+
+        let changed = uri
+            .with(&object(&[("path", JsValue::from_str("/other"))]))
+            .unwrap();
+        assert_eq!(
+            changed.to_string_js(None),
+            "foo://auth/other?query#fragment"
+        );
+
+        // `null` clears a component, `undefined` keeps it
+        let changed = uri
+            .with(&object(&[
+                ("query", JsValue::NULL),
+                ("fragment", JsValue::UNDEFINED),
+            ]))
+            .unwrap();
+        assert_eq!(changed.to_string_js(None), "foo://auth/path#fragment");
+
+        assert!(uri
+            .with(&object(&[("path", JsValue::from_f64(1.0))]))
+            .is_err());
+        assert!(uri
+            .with(&object(&[("path", JsValue::from_str("relative"))]))
+            .is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_to_json() {
+        let uri = WasmUri::parse("foo://auth/path?query", None).unwrap();
+        let json = uri.to_json().unwrap();
+        assert_eq!(property(&json, "$mid").as_f64(), Some(1.0));
+        assert_eq!(
+            property(&json, "scheme").as_string().as_deref(),
+            Some("foo")
+        );
+        assert_eq!(
+            property(&json, "authority").as_string().as_deref(),
+            Some("auth")
+        );
+        assert_eq!(
+            property(&json, "path").as_string().as_deref(),
+            Some("/path")
+        );
+        assert_eq!(
+            property(&json, "query").as_string().as_deref(),
+            Some("query")
+        );
+        assert!(property(&json, "fragment").is_undefined());
+
+        let revived = WasmUri::from_components(&json).unwrap();
+        assert_eq!(revived.to_string_js(None), uri.to_string_js(None));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_utils() {
+        let uri = WasmUri::parse("foo://a/some/file/test.txt", None).unwrap();
+        assert_eq!(WasmUtils::basename(&uri), "test.txt");
+        assert_eq!(WasmUtils::extname(&uri), ".txt");
+        assert_eq!(
+            WasmUtils::dirname(&uri).unwrap().to_string_js(None),
+            "foo://a/some/file"
+        );
+        assert_eq!(
+            WasmUtils::join_path(&uri, vec!["..".to_string(), "other.txt".to_string()])
+                .unwrap()
+                .to_string_js(None),
+            "foo://a/some/file/other.txt"
+        );
+        assert_eq!(
+            WasmUtils::resolve_path(&uri, vec!["/x".to_string()])
+                .unwrap()
+                .to_string_js(None),
+            "foo://a/x"
+        );
+    }
+}